@@ -0,0 +1,251 @@
+//! Thin REST client for talking to an Attuned HTTP server.
+//!
+//! The CLI is a synchronous-feeling tool, but exposes the same `async fn`
+//! surface regardless of transport, so command handlers in `main.rs` are
+//! written once. By default requests go out over [`reqwest`]; with the
+//! `blocking` feature enabled, the same methods instead drive a [`ureq`]
+//! client on a blocking thread via [`tokio::task::spawn_blocking`]. Both
+//! transports share the URL-building and (de)serialization logic below.
+
+use attuned_http::handlers::{ContextResponse, ErrorResponse, StateResponse, UpsertStateRequest};
+use std::collections::BTreeMap;
+
+/// Errors talking to an Attuned server.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The server returned an error response.
+    #[error("server returned {status}: {message}")]
+    Server {
+        /// HTTP status code.
+        status: u16,
+        /// Error message from the response body (or the raw body if it
+        /// didn't parse as an [`ErrorResponse`]).
+        message: String,
+    },
+    /// The request could not be sent, or the response could not be read.
+    #[error("request failed: {0}")]
+    Transport(String),
+    /// The response body could not be parsed as JSON.
+    #[error("failed to parse response: {0}")]
+    Decode(#[from] serde_json::Error),
+}
+
+/// Parse an `axis=value` pair from the CLI's `--axis` flag.
+pub fn parse_axis(input: &str) -> Result<(String, f32), ClientError> {
+    let (name, value) = input.split_once('=').ok_or_else(|| {
+        ClientError::Transport(format!("invalid --axis '{input}', expected axis=value"))
+    })?;
+    let value: f32 = value
+        .parse()
+        .map_err(|_| ClientError::Transport(format!("invalid axis value '{value}' for axis '{name}'")))?;
+    Ok((name.to_string(), value))
+}
+
+/// Build an [`UpsertStateRequest`] from a user id and parsed `--axis` flags.
+pub fn build_upsert_request(user_id: &str, axis: &[String]) -> Result<UpsertStateRequest, ClientError> {
+    let mut axes = BTreeMap::new();
+    for raw in axis {
+        let (name, value) = parse_axis(raw)?;
+        axes.insert(name, value);
+    }
+    Ok(UpsertStateRequest {
+        user_id: user_id.to_string(),
+        source: Default::default(),
+        confidence: 1.0,
+        axes,
+        message: None,
+    })
+}
+
+fn endpoint(base_url: &str, path: &str) -> String {
+    format!("{}{}", base_url.trim_end_matches('/'), path)
+}
+
+/// Turn a non-2xx status + body into a [`ClientError::Server`].
+fn server_error(status: u16, body: &str) -> ClientError {
+    let message = serde_json::from_str::<ErrorResponse>(body)
+        .map(|e| e.error.message)
+        .unwrap_or_else(|_| body.to_string());
+    ClientError::Server { status, message }
+}
+
+#[cfg(not(feature = "blocking"))]
+mod transport {
+    use super::*;
+
+    /// REST client for the Attuned HTTP server.
+    pub struct AttunedClient {
+        base_url: String,
+        http: reqwest::Client,
+    }
+
+    impl AttunedClient {
+        /// Create a client targeting the given server base URL.
+        pub fn new(base_url: impl Into<String>) -> Self {
+            Self {
+                base_url: base_url.into(),
+                http: reqwest::Client::new(),
+            }
+        }
+
+        /// `POST /v1/state`.
+        pub async fn upsert_state(&self, request: &UpsertStateRequest) -> Result<StateResponse, ClientError> {
+            let response = self
+                .http
+                .post(endpoint(&self.base_url, "/v1/state"))
+                .json(request)
+                .send()
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?;
+            Self::decode(response).await
+        }
+
+        /// `GET /v1/state/{user_id}`.
+        pub async fn get_state(&self, user_id: &str) -> Result<StateResponse, ClientError> {
+            let response = self
+                .http
+                .get(endpoint(&self.base_url, &format!("/v1/state/{user_id}")))
+                .send()
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?;
+            Self::decode(response).await
+        }
+
+        /// `DELETE /v1/state/{user_id}`.
+        pub async fn delete_state(&self, user_id: &str) -> Result<(), ClientError> {
+            let response = self
+                .http
+                .delete(endpoint(&self.base_url, &format!("/v1/state/{user_id}")))
+                .send()
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                let status = response.status().as_u16();
+                let body = response.text().await.unwrap_or_default();
+                Err(server_error(status, &body))
+            }
+        }
+
+        /// `GET /v1/context/{user_id}`.
+        pub async fn get_context(&self, user_id: &str) -> Result<ContextResponse, ClientError> {
+            let response = self
+                .http
+                .get(endpoint(&self.base_url, &format!("/v1/context/{user_id}")))
+                .send()
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?;
+            Self::decode(response).await
+        }
+
+        /// `GET /health`.
+        pub async fn health(&self) -> Result<bool, ClientError> {
+            let response = self
+                .http
+                .get(endpoint(&self.base_url, "/health"))
+                .send()
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?;
+            Ok(response.status().is_success())
+        }
+
+        async fn decode<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T, ClientError> {
+            let status = response.status();
+            let body = response.text().await.map_err(|e| ClientError::Transport(e.to_string()))?;
+            if status.is_success() {
+                Ok(serde_json::from_str(&body)?)
+            } else {
+                Err(server_error(status.as_u16(), &body))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+mod transport {
+    use super::*;
+
+    /// REST client for the Attuned HTTP server, backed by a blocking [`ureq`]
+    /// agent, each call run on Tokio's blocking thread pool.
+    pub struct AttunedClient {
+        base_url: String,
+    }
+
+    impl AttunedClient {
+        /// Create a client targeting the given server base URL.
+        pub fn new(base_url: impl Into<String>) -> Self {
+            Self { base_url: base_url.into() }
+        }
+
+        /// `POST /v1/state`.
+        pub async fn upsert_state(&self, request: &UpsertStateRequest) -> Result<StateResponse, ClientError> {
+            let url = endpoint(&self.base_url, "/v1/state");
+            let body = serde_json::to_string(request)?;
+            Self::spawn(move || {
+                let response = ureq::post(&url)
+                    .send_json(serde_json::from_str::<serde_json::Value>(&body).unwrap());
+                Self::decode(response)
+            })
+            .await
+        }
+
+        /// `GET /v1/state/{user_id}`.
+        pub async fn get_state(&self, user_id: &str) -> Result<StateResponse, ClientError> {
+            let url = endpoint(&self.base_url, &format!("/v1/state/{user_id}"));
+            Self::spawn(move || Self::decode(ureq::get(&url).call())).await
+        }
+
+        /// `DELETE /v1/state/{user_id}`.
+        pub async fn delete_state(&self, user_id: &str) -> Result<(), ClientError> {
+            let url = endpoint(&self.base_url, &format!("/v1/state/{user_id}"));
+            Self::spawn(move || match ureq::delete(&url).call() {
+                Ok(_) => Ok(()),
+                Err(ureq::Error::Status(status, response)) => {
+                    let body = response.into_string().unwrap_or_default();
+                    Err(server_error(status, &body))
+                }
+                Err(e) => Err(ClientError::Transport(e.to_string())),
+            })
+            .await
+        }
+
+        /// `GET /v1/context/{user_id}`.
+        pub async fn get_context(&self, user_id: &str) -> Result<ContextResponse, ClientError> {
+            let url = endpoint(&self.base_url, &format!("/v1/context/{user_id}"));
+            Self::spawn(move || Self::decode(ureq::get(&url).call())).await
+        }
+
+        /// `GET /health`.
+        pub async fn health(&self) -> Result<bool, ClientError> {
+            let url = endpoint(&self.base_url, "/health");
+            Self::spawn(move || Ok(ureq::get(&url).call().is_ok())).await
+        }
+
+        async fn spawn<T, F>(f: F) -> Result<T, ClientError>
+        where
+            T: Send + 'static,
+            F: FnOnce() -> Result<T, ClientError> + Send + 'static,
+        {
+            tokio::task::spawn_blocking(f)
+                .await
+                .map_err(|e| ClientError::Transport(e.to_string()))?
+        }
+
+        fn decode<T: serde::de::DeserializeOwned>(result: Result<ureq::Response, ureq::Error>) -> Result<T, ClientError> {
+            match result {
+                Ok(response) => {
+                    let body = response.into_string().map_err(|e| ClientError::Transport(e.to_string()))?;
+                    Ok(serde_json::from_str(&body)?)
+                }
+                Err(ureq::Error::Status(status, response)) => {
+                    let body = response.into_string().unwrap_or_default();
+                    Err(server_error(status, &body))
+                }
+                Err(e) => Err(ClientError::Transport(e.to_string())),
+            }
+        }
+    }
+}
+
+pub use transport::AttunedClient;
@@ -1,8 +1,10 @@
 //! Attuned CLI tool for development and testing.
 
+mod client;
+
 use attuned_http::{Server, ServerConfig};
-use attuned_store::MemoryStore;
 use clap::{Parser, Subcommand};
+use client::AttunedClient;
 
 #[derive(Parser)]
 #[command(name = "attuned")]
@@ -32,6 +34,20 @@ enum OutputFormat {
     Quiet,
 }
 
+impl OutputFormat {
+    /// Render a value according to this format.
+    fn print<T: serde::Serialize + std::fmt::Debug>(&self, value: &T) {
+        match self {
+            OutputFormat::Json => match serde_json::to_string_pretty(value) {
+                Ok(json) => println!("{json}"),
+                Err(e) => eprintln!("failed to serialize response: {e}"),
+            },
+            OutputFormat::Pretty => println!("{value:#?}"),
+            OutputFormat::Quiet => {}
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// State management commands.
@@ -51,6 +67,13 @@ enum Commands {
         /// Port to listen on.
         #[arg(short, long, default_value = "8080")]
         port: u16,
+        /// Storage backend connection string, dispatched by scheme the same
+        /// way `attuned_http::backend::connect` does: `memory://` (the
+        /// default) keeps state in-process; `file:///path/to.db` uses
+        /// `attuned-sqlite`; `sqlite://...`/`postgres://...` use
+        /// `attuned-sql`'s `SqlStore`.
+        #[arg(long, default_value = "memory://")]
+        store: String,
     },
     /// Check server health.
     Health,
@@ -78,31 +101,39 @@ enum StateCommands {
     },
 }
 
+/// The default server URL used when `--server`/`ATTUNED_SERVER` isn't set.
+const DEFAULT_SERVER: &str = "http://127.0.0.1:8080";
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
     let cli = Cli::parse();
+    let format = cli.format.clone();
+    let client = AttunedClient::new(cli.server.clone().unwrap_or_else(|| DEFAULT_SERVER.to_string()));
 
     match cli.command {
         Commands::State { command } => match command {
             StateCommands::Get { user_id } => {
-                println!("Getting state for user: {}", user_id);
-                // TODO: Implement (TASK-007)
+                let state = client.get_state(&user_id).await?;
+                format.print(&state);
             }
             StateCommands::Set { user_id, axis } => {
-                println!("Setting state for user: {} with axes: {:?}", user_id, axis);
-                // TODO: Implement (TASK-007)
+                let request = client::build_upsert_request(&user_id, &axis)?;
+                let state = client.upsert_state(&request).await?;
+                format.print(&state);
             }
             StateCommands::Delete { user_id } => {
-                println!("Deleting state for user: {}", user_id);
-                // TODO: Implement (TASK-007)
+                client.delete_state(&user_id).await?;
+                if !matches!(format, OutputFormat::Quiet) {
+                    println!("Deleted state for user: {user_id}");
+                }
             }
         },
         Commands::Translate { user_id } => {
-            println!("Translating state for user: {}", user_id);
-            // TODO: Implement (TASK-007)
+            let context = client.get_context(&user_id).await?;
+            format.print(&context);
         }
         Commands::Axes => {
             println!("Available axes:");
@@ -110,35 +141,42 @@ async fn main() -> anyhow::Result<()> {
                 println!("  {} ({}): {}", axis.name, axis.category, axis.description);
             }
         }
-        Commands::Serve { port } => {
+        Commands::Serve { port, store } => {
             let bind_addr = format!("127.0.0.1:{}", port).parse()?;
             let config = ServerConfig {
                 bind_addr,
                 ..Default::default()
             };
 
-            let store = MemoryStore::default();
-            let server = Server::new(store, config);
-
             println!("Starting Attuned server on http://127.0.0.1:{}", port);
             println!("Endpoints:");
             println!("  POST   /v1/state          - Upsert state");
             println!("  GET    /v1/state/{{user}}   - Get state");
             println!("  GET    /v1/context/{{user}} - Get translated context");
             println!("  DELETE /v1/state/{{user}}   - Delete state");
+            println!("  POST   /v1/state/batch   - Upsert state for many users");
+            println!("  POST   /v1/state/query   - Get state for many users");
+            println!("  POST   /v1/auth/token     - Exchange an API key for a session token");
             println!("  GET    /health            - Health check");
             println!();
             println!("Press Ctrl+C to stop");
 
-            server.run().await?;
+            let store = if store.is_empty() { "memory://".to_string() } else { store };
+            println!("Storage backend: {store}");
+            Server::from_uri(&store, config).await?.run().await?;
         }
         Commands::Health => {
-            if let Some(server) = cli.server {
-                println!("Checking health of server: {}", server);
+            let healthy = client.health().await?;
+            if matches!(format, OutputFormat::Quiet) {
+                // Exit code alone communicates health to scripts.
+            } else if healthy {
+                println!("OK");
             } else {
-                println!("No server specified, checking local health...");
+                println!("UNHEALTHY");
+            }
+            if !healthy {
+                std::process::exit(1);
             }
-            // TODO: Implement (TASK-007)
         }
     }
 
@@ -0,0 +1,118 @@
+//! Structured API keys carrying identity and per-key rate-limit tiers.
+//!
+//! Unlike [`crate::middleware::StaticKeyAuthenticator`], which treats a key
+//! as an opaque string matched for equality, this module parses the bearer
+//! token into a [`ApiKey`] (a ULID or UUID) and looks it up against a table
+//! of [`ApiKeyRecord`]s so the server knows *who* is calling and what tier
+//! they're on.
+
+use crate::middleware::{Authenticator, AuthenticationError, Identity};
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// A structured API key, parsed from a bearer token as either a ULID or a UUID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ApiKey {
+    /// A [ulid](https://github.com/ulid/spec)-formatted key.
+    Ulid(ulid::Ulid),
+    /// A UUID-formatted key.
+    Uuid(uuid::Uuid),
+}
+
+/// Error parsing a bearer token into a structured [`ApiKey`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("token is not a valid ULID or UUID")]
+pub struct ParseApiKeyError;
+
+impl FromStr for ApiKey {
+    type Err = ParseApiKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(ulid) = ulid::Ulid::from_string(s) {
+            return Ok(ApiKey::Ulid(ulid));
+        }
+        if let Ok(uuid) = uuid::Uuid::parse_str(s) {
+            return Ok(ApiKey::Uuid(uuid));
+        }
+        Err(ParseApiKeyError)
+    }
+}
+
+/// The record an [`ApiKey`] resolves to.
+#[derive(Debug, Clone)]
+pub struct ApiKeyRecord {
+    /// Stable identifier of the key's owner (distinct from the key itself,
+    /// so a key can be rotated without changing who it represents).
+    pub owner_id: String,
+    /// Human-readable description, shown in audit logs and admin UIs.
+    pub description: String,
+    /// Rate-limit / feature tier this key belongs to.
+    pub tier: String,
+}
+
+impl ApiKeyRecord {
+    /// Create a new record for the given owner and tier.
+    pub fn new(owner_id: impl Into<String>, description: impl Into<String>, tier: impl Into<String>) -> Self {
+        Self {
+            owner_id: owner_id.into(),
+            description: description.into(),
+            tier: tier.into(),
+        }
+    }
+}
+
+/// [`Authenticator`] backed by a lookup table of structured API keys.
+pub struct StructuredKeyAuthenticator {
+    keys: HashMap<ApiKey, ApiKeyRecord>,
+    public_paths: HashSet<String>,
+}
+
+impl StructuredKeyAuthenticator {
+    /// Build an authenticator from a table of keys to records.
+    pub fn new(keys: HashMap<ApiKey, ApiKeyRecord>) -> Self {
+        Self {
+            keys,
+            public_paths: ["/health", "/ready"].iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Add a path that doesn't require authentication.
+    pub fn add_public_path(mut self, path: impl Into<String>) -> Self {
+        self.public_paths.insert(path.into());
+        self
+    }
+}
+
+#[async_trait]
+impl Authenticator for StructuredKeyAuthenticator {
+    async fn authenticate(&self, headers: &HeaderMap, path: &str) -> Result<Identity, AuthenticationError> {
+        if self.public_paths.contains(path) {
+            return Ok(Identity {
+                user_id: "anonymous".to_string(),
+                tier: "anonymous".to_string(),
+                scopes: vec![],
+            });
+        }
+
+        let header = headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or(AuthenticationError::MissingCredentials)?;
+
+        let token = header
+            .strip_prefix("Bearer ")
+            .ok_or(AuthenticationError::InvalidCredentials)?;
+
+        let key = ApiKey::from_str(token).map_err(|_| AuthenticationError::InvalidCredentials)?;
+
+        let record = self.keys.get(&key).ok_or(AuthenticationError::InvalidCredentials)?;
+
+        Ok(Identity {
+            user_id: record.owner_id.clone(),
+            tier: record.tier.clone(),
+            scopes: vec![],
+        })
+    }
+}
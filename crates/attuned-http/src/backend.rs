@@ -0,0 +1,205 @@
+//! Connection-URI-based store backend selection.
+//!
+//! Lets ops point a single `attuned-http` binary at different persistence
+//! layers purely via configuration — no recompiling against a different
+//! concrete [`StateStore`] — the way a blobstore selector dispatches on a
+//! URI scheme (`s3://`, `file://`, ...).
+
+use attuned_core::{ComponentHealth, HealthCheck, StateSnapshot};
+use attuned_store::{MemoryStore, StateStore, StoreError};
+use std::sync::Arc;
+
+/// Object-safe union of the bounds [`crate::Server`] needs from its store.
+///
+/// `StateStore` and `HealthCheck` can't both be used directly as the base of
+/// one trait object (`dyn StateStore + HealthCheck` isn't valid Rust); this
+/// marker trait gives them a single object-safe name, with a blanket impl so
+/// every existing store satisfies it for free.
+pub trait DynStore: StateStore + HealthCheck {}
+
+impl<T: StateStore + HealthCheck> DynStore for T {}
+
+/// Errors selecting or connecting a backend from a connection URI.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum BackendError {
+    /// The URI didn't have a `scheme://` prefix at all.
+    #[error("malformed store URI (expected scheme://...): {uri}")]
+    MalformedUri {
+        /// The original URI string.
+        uri: String,
+    },
+    /// The scheme isn't one `connect` knows how to dispatch.
+    #[error("unsupported store URI scheme: {scheme}")]
+    UnsupportedScheme {
+        /// The rejected scheme, without the trailing `://`.
+        scheme: String,
+    },
+    /// The scheme was recognized but the backend isn't compiled in (its
+    /// feature flag is disabled).
+    #[error("store scheme '{scheme}' requires the '{feature}' feature")]
+    FeatureDisabled {
+        /// The scheme that was recognized.
+        scheme: String,
+        /// The feature flag that would enable it.
+        feature: String,
+    },
+    /// Connecting to the selected backend failed.
+    #[error("failed to connect to store backend: {0}")]
+    Connect(String),
+}
+
+/// A store behind type erasure, selected at runtime by [`connect`].
+///
+/// Delegates every [`StateStore`]/[`HealthCheck`] method to the boxed
+/// backend, matching the decorator pattern used by
+/// [`attuned_store::PooledStore`] and [`attuned_store::ReplicatedStore`].
+#[derive(Clone)]
+pub struct BoxedStore(Arc<dyn DynStore>);
+
+#[async_trait::async_trait]
+impl StateStore for BoxedStore {
+    async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+        self.0.upsert_latest(snapshot).await
+    }
+
+    async fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
+        self.0.get_latest(user_id).await
+    }
+
+    async fn delete(&self, user_id: &str) -> Result<(), StoreError> {
+        self.0.delete(user_id).await
+    }
+
+    async fn get_history(
+        &self,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<StateSnapshot>, StoreError> {
+        self.0.get_history(user_id, limit).await
+    }
+
+    async fn health_check(&self) -> Result<bool, StoreError> {
+        self.0.health_check().await
+    }
+
+    async fn upsert_many(&self, snapshots: Vec<StateSnapshot>) -> Vec<Result<(), StoreError>> {
+        self.0.upsert_many(snapshots).await
+    }
+
+    async fn get_many(
+        &self,
+        user_ids: &[String],
+    ) -> Vec<Result<Option<StateSnapshot>, StoreError>> {
+        self.0.get_many(user_ids).await
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthCheck for BoxedStore {
+    async fn check(&self) -> ComponentHealth {
+        self.0.check().await
+    }
+}
+
+/// Connect to a [`BoxedStore`] chosen by `uri`'s scheme:
+/// - `memory://` — [`attuned_store::MemoryStore`]; nothing survives a restart.
+/// - `file:///path/to.db` — `attuned_sqlite::SqliteStore` at that path
+///   (requires the `sqlite` feature).
+/// - `sqlite://...` — `attuned_sql::SqlStore` against that connection string,
+///   the scheme `attuned_sql`'s own docs recommend (requires the `postgres`
+///   feature, which gates the `attuned-sql` dependency).
+/// - `postgres://...` / `postgresql://...` — `attuned_sql::SqlStore` against
+///   that connection string (requires the `postgres` feature).
+pub async fn connect(uri: &str) -> Result<BoxedStore, BackendError> {
+    let scheme = uri
+        .split_once("://")
+        .map(|(scheme, _)| scheme)
+        .ok_or_else(|| BackendError::MalformedUri {
+            uri: uri.to_string(),
+        })?;
+
+    match scheme {
+        "memory" => Ok(BoxedStore(Arc::new(MemoryStore::default()))),
+
+        "file" => {
+            #[cfg(feature = "sqlite")]
+            {
+                let path = uri.trim_start_matches("file://");
+                let config = attuned_sqlite::SqliteStoreConfig {
+                    database_url: format!("sqlite://{path}"),
+                    ..Default::default()
+                };
+                let store = attuned_sqlite::SqliteStore::new(config)
+                    .await
+                    .map_err(|e| BackendError::Connect(e.to_string()))?;
+                Ok(BoxedStore(Arc::new(store)))
+            }
+            #[cfg(not(feature = "sqlite"))]
+            Err(BackendError::FeatureDisabled {
+                scheme: scheme.to_string(),
+                feature: "sqlite".to_string(),
+            })
+        }
+
+        "sqlite" | "postgres" | "postgresql" => {
+            #[cfg(feature = "postgres")]
+            {
+                let config = attuned_sql::SqlStoreConfig {
+                    database_url: uri.to_string(),
+                    ..Default::default()
+                };
+                let store = attuned_sql::SqlStore::new(config)
+                    .await
+                    .map_err(|e| BackendError::Connect(e.to_string()))?;
+                Ok(BoxedStore(Arc::new(store)))
+            }
+            #[cfg(not(feature = "postgres"))]
+            Err(BackendError::FeatureDisabled {
+                scheme: scheme.to_string(),
+                feature: "postgres".to_string(),
+            })
+        }
+
+        other => Err(BackendError::UnsupportedScheme {
+            scheme: other.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_scheme_connects() {
+        let store = connect("memory://").await.unwrap();
+        assert!(store.health_check().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_scheme_is_rejected() {
+        let err = connect("s3://bucket/key").await.unwrap_err();
+        assert!(matches!(err, BackendError::UnsupportedScheme { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_uri_is_rejected() {
+        let err = connect("not-a-uri").await.unwrap_err();
+        assert!(matches!(err, BackendError::MalformedUri { .. }));
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    #[tokio::test]
+    async fn test_sqlite_scheme_requires_postgres_feature() {
+        let err = connect("sqlite://attuned_test.db").await.unwrap_err();
+        assert!(matches!(err, BackendError::FeatureDisabled { .. }));
+    }
+
+    #[cfg(feature = "postgres")]
+    #[tokio::test]
+    async fn test_sqlite_scheme_connects_via_attuned_sql() {
+        let store = connect("sqlite://:memory:").await.unwrap();
+        assert!(store.health_check().await.unwrap());
+    }
+}
@@ -1,6 +1,8 @@
 //! Server configuration.
 
-use crate::middleware::{AuthConfig, RateLimitConfig};
+use crate::cors::CorsConfig;
+use crate::jwt_auth::JwtAuthConfig;
+use crate::middleware::{AuthConfig, ConcurrencyLimitConfig, RateLimitConfig};
 use std::net::SocketAddr;
 use std::time::Duration;
 
@@ -22,18 +24,28 @@ pub struct ServerConfig {
     /// Default: 1MB
     pub body_limit: usize,
 
-    /// CORS allowed origins.
-    /// Default: empty (CORS disabled)
-    pub cors_origins: Vec<String>,
+    /// Cross-origin resource sharing configuration.
+    /// Default: disabled (same-origin only)
+    pub cors: CorsConfig,
 
     /// API key authentication configuration.
     /// Default: disabled (no keys configured)
     pub auth: AuthConfig,
 
+    /// Per-user JWT authentication, asserted against path user ids by
+    /// `/v1/state/{user_id}` and friends.
+    /// Default: disabled
+    pub jwt: JwtAuthConfig,
+
     /// Rate limiting configuration.
     /// Default: 100 requests per minute per IP
     pub rate_limit: RateLimitConfig,
 
+    /// Concurrency limiting configuration, bounding in-flight requests per
+    /// client rather than requests per window.
+    /// Default: 10 concurrent requests per IP
+    pub concurrency_limit: ConcurrencyLimitConfig,
+
     /// Enable security headers.
     /// Default: true
     pub security_headers: bool,
@@ -47,6 +59,12 @@ pub struct ServerConfig {
     /// Default: None (uses InferenceConfig::default())
     #[cfg(feature = "inference")]
     pub inference_config: Option<InferenceConfig>,
+
+    /// Maximum request body size for `/v1/infer` and `/v1/infer/stream`,
+    /// which can carry a longer message than a state upsert.
+    /// Default: None (8x `body_limit`)
+    #[cfg(feature = "inference")]
+    pub inference_body_limit: Option<usize>,
 }
 
 impl Default for ServerConfig {
@@ -56,14 +74,18 @@ impl Default for ServerConfig {
             bind_addr: "127.0.0.1:8080".parse().unwrap(),
             request_timeout: Duration::from_secs(30),
             body_limit: 1024 * 1024, // 1MB
-            cors_origins: vec![],
+            cors: CorsConfig::default(),
             auth: AuthConfig::default(),
+            jwt: JwtAuthConfig::default(),
             rate_limit: RateLimitConfig::default(),
+            concurrency_limit: ConcurrencyLimitConfig::default(),
             security_headers: true,
             #[cfg(feature = "inference")]
             enable_inference: false,
             #[cfg(feature = "inference")]
             inference_config: None,
+            #[cfg(feature = "inference")]
+            inference_body_limit: None,
         }
     }
 }
@@ -75,6 +97,12 @@ impl ServerConfig {
         self
     }
 
+    /// Enable per-user JWT authentication, asserted against path user ids.
+    pub fn with_jwt(mut self, jwt: JwtAuthConfig) -> Self {
+        self.jwt = jwt;
+        self
+    }
+
     /// Disable rate limiting.
     pub fn without_rate_limit(mut self) -> Self {
         self.rate_limit.max_requests = u32::MAX;
@@ -88,6 +116,44 @@ impl ServerConfig {
         self
     }
 
+    /// Disable the concurrency limit.
+    pub fn without_concurrency_limit(mut self) -> Self {
+        self.concurrency_limit.max_concurrent_requests = tokio::sync::Semaphore::MAX_PERMITS;
+        self
+    }
+
+    /// Set the maximum number of concurrent in-flight requests per client.
+    pub fn with_concurrency_limit(mut self, max_concurrent_requests: usize) -> Self {
+        self.concurrency_limit.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Set the maximum request body size, in bytes.
+    pub fn with_body_limit(mut self, bytes: usize) -> Self {
+        self.body_limit = bytes;
+        self
+    }
+
+    /// Override the body size ceiling for `/v1/infer`/`/v1/infer/stream`,
+    /// instead of the default (8x `body_limit`).
+    #[cfg(feature = "inference")]
+    pub fn with_inference_body_limit(mut self, bytes: usize) -> Self {
+        self.inference_body_limit = Some(bytes);
+        self
+    }
+
+    /// The effective body size ceiling for the inference routes.
+    #[cfg(feature = "inference")]
+    pub fn effective_inference_body_limit(&self) -> usize {
+        self.inference_body_limit.unwrap_or(self.body_limit * 8)
+    }
+
+    /// Enable CORS for the given origins.
+    pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+        self.cors = cors;
+        self
+    }
+
     /// Enable inference from message text.
     #[cfg(feature = "inference")]
     pub fn with_inference(mut self) -> Self {
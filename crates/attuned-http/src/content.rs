@@ -0,0 +1,126 @@
+//! Content negotiation between JSON and CBOR.
+//!
+//! High-throughput clients and embedded agents can avoid JSON overhead on
+//! the hot `/v1/state` and `/v1/context` paths by sending
+//! `Content-Type: application/cbor` and `Accept: application/cbor`. The
+//! request/response types themselves (`UpsertStateRequest`, `StateResponse`,
+//! etc.) are untouched — only the wire encoding changes.
+
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{header, request::Parts, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+const CBOR_MEDIA_TYPE: &str = "application/cbor";
+
+/// Which wire format a request body was sent in / a response should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// `application/json` (the default).
+    #[default]
+    Json,
+    /// `application/cbor`.
+    Cbor,
+}
+
+impl Encoding {
+    fn from_media_type(value: Option<&HeaderValue>) -> Self {
+        match value.and_then(|v| v.to_str().ok()) {
+            Some(s) if s.contains(CBOR_MEDIA_TYPE) => Encoding::Cbor,
+            _ => Encoding::Json,
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Encoding::Json => "application/json",
+            Encoding::Cbor => CBOR_MEDIA_TYPE,
+        }
+    }
+}
+
+/// Extractor that reads the negotiated response encoding from the request's
+/// `Accept` header, defaulting to JSON.
+///
+/// Handlers extract this alongside their body extractor, then pass it to
+/// [`Negotiated`] when building the response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NegotiatedEncoding(pub Encoding);
+
+impl<S> FromRequestParts<S> for NegotiatedEncoding
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(Encoding::from_media_type(parts.headers.get(header::ACCEPT))))
+    }
+}
+
+/// Extractor that deserializes a request body as JSON or CBOR depending on
+/// `Content-Type`, defaulting to JSON when absent.
+pub struct NegotiatedBody<T>(pub T);
+
+impl<S, T> FromRequest<S> for NegotiatedBody<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let encoding = Encoding::from_media_type(req.headers().get(header::CONTENT_TYPE));
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()).into_response())?;
+
+        let value = match encoding {
+            Encoding::Json => serde_json::from_slice(&bytes)
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid JSON body: {e}")).into_response())?,
+            Encoding::Cbor => ciborium::de::from_reader(bytes.as_ref())
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid CBOR body: {e}")).into_response())?,
+        };
+
+        Ok(Self(value))
+    }
+}
+
+/// Response wrapper that serializes `T` as JSON or CBOR according to an
+/// [`Encoding`] resolved from the request's `Accept` header.
+pub struct Negotiated<T> {
+    encoding: Encoding,
+    body: T,
+}
+
+impl<T> Negotiated<T> {
+    /// Wrap `body`, to be serialized according to `encoding`.
+    pub fn new(encoding: NegotiatedEncoding, body: T) -> Self {
+        Self {
+            encoding: encoding.0,
+            body,
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        let content_type = self.encoding.content_type();
+        match self.encoding {
+            Encoding::Json => match serde_json::to_vec(&self.body) {
+                Ok(bytes) => (StatusCode::OK, [(header::CONTENT_TYPE, content_type)], bytes).into_response(),
+                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+            },
+            Encoding::Cbor => {
+                let mut bytes = Vec::new();
+                match ciborium::ser::into_writer(&self.body, &mut bytes) {
+                    Ok(()) => (StatusCode::OK, [(header::CONTENT_TYPE, content_type)], bytes).into_response(),
+                    Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+                }
+            }
+        }
+    }
+}
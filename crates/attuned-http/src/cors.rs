@@ -0,0 +1,140 @@
+//! Configurable cross-origin resource sharing.
+//!
+//! Disabled by default: the routes were never designed to be called directly
+//! from a browser, so enabling CORS is an explicit opt-in rather than
+//! something [`ServerConfig`](crate::ServerConfig) turns on for you.
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Which origins a CORS-enabled response may be shared with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum CorsOrigins {
+    /// No cross-origin requests allowed; [`CorsConfig::to_layer`] returns
+    /// `None` and no `CorsLayer` is installed.
+    #[default]
+    Disabled,
+    /// Any origin may access the API (`Access-Control-Allow-Origin: *`).
+    /// Rejected together with `allow_credentials: true`, since browsers
+    /// refuse to honor that combination.
+    Any,
+    /// Only the listed origins (e.g. `https://app.example.com`).
+    List(Vec<String>),
+}
+
+/// CORS configuration for [`crate::Server::router`].
+#[derive(Clone, Debug)]
+pub struct CorsConfig {
+    /// Origins allowed to make cross-origin requests.
+    /// Default: [`CorsOrigins::Disabled`]
+    pub origins: CorsOrigins,
+    /// Methods allowed on cross-origin requests.
+    /// Default: GET, POST, DELETE
+    pub allowed_methods: Vec<Method>,
+    /// Headers allowed on cross-origin requests.
+    /// Default: `content-type`, `authorization`
+    pub allowed_headers: Vec<HeaderName>,
+    /// Whether to send `Access-Control-Allow-Credentials: true`.
+    /// Default: false
+    pub allow_credentials: bool,
+    /// How long a browser may cache a preflight response.
+    /// Default: 10 minutes
+    pub max_age: Duration,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            origins: CorsOrigins::default(),
+            allowed_methods: vec![Method::GET, Method::POST, Method::DELETE],
+            allowed_headers: vec![HeaderName::from_static("content-type"), HeaderName::from_static("authorization")],
+            allow_credentials: false,
+            max_age: Duration::from_secs(600),
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Enable CORS for the given list of origins.
+    pub fn with_origins(mut self, origins: impl IntoIterator<Item = String>) -> Self {
+        self.origins = CorsOrigins::List(origins.into_iter().collect());
+        self
+    }
+
+    /// Enable CORS for any origin.
+    pub fn permissive() -> Self {
+        Self {
+            origins: CorsOrigins::Any,
+            ..Self::default()
+        }
+    }
+
+    /// Build the `tower_http` layer this config describes, or `None` when
+    /// CORS is disabled (the common, same-origin-only case) or the config
+    /// combines [`CorsOrigins::Any`] with `allow_credentials: true` — a
+    /// combination `CorsLayer` would otherwise panic on at request time.
+    pub fn to_layer(&self) -> Option<CorsLayer> {
+        if self.origins == CorsOrigins::Any && self.allow_credentials {
+            tracing::warn!(
+                "CORS config combines CorsOrigins::Any with allow_credentials: true, \
+                 which browsers reject and tower_http's CorsLayer panics on; disabling CORS"
+            );
+            return None;
+        }
+
+        let allow_origin = match &self.origins {
+            CorsOrigins::Disabled => return None,
+            CorsOrigins::Any => AllowOrigin::any(),
+            CorsOrigins::List(origins) => {
+                let parsed: Vec<HeaderValue> = origins
+                    .iter()
+                    .filter_map(|origin| HeaderValue::from_str(origin).ok())
+                    .collect();
+                AllowOrigin::list(parsed)
+            }
+        };
+
+        let mut layer = CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(self.allowed_methods.clone())
+            .allow_headers(self.allowed_headers.clone())
+            .max_age(self.max_age);
+
+        if self.allow_credentials {
+            layer = layer.allow_credentials(true);
+        }
+
+        Some(layer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_by_default_builds_no_layer() {
+        assert!(CorsConfig::default().to_layer().is_none());
+    }
+
+    #[test]
+    fn test_explicit_origins_build_a_layer() {
+        let config = CorsConfig::default().with_origins(["https://app.example.com".to_string()]);
+        assert!(config.to_layer().is_some());
+    }
+
+    #[test]
+    fn test_permissive_builds_a_layer() {
+        assert!(CorsConfig::permissive().to_layer().is_some());
+    }
+
+    #[test]
+    fn test_any_origin_with_credentials_is_rejected() {
+        let config = CorsConfig {
+            allow_credentials: true,
+            ..CorsConfig::permissive()
+        };
+        assert!(config.to_layer().is_none());
+    }
+}
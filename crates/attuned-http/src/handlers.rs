@@ -1,5 +1,9 @@
 //! HTTP request handlers.
 
+use crate::content::{Negotiated, NegotiatedBody, NegotiatedEncoding};
+use crate::jwt_auth::{AuthError, JwtAuthConfig, Principal};
+use crate::middleware::AuthConfig;
+use crate::tokens::{TokenConfig, TokenStore};
 use attuned_core::{
     HealthCheck, HealthState, HealthStatus, PromptContext, RuleTranslator, Source, StateSnapshot,
     Translator,
@@ -7,7 +11,7 @@ use attuned_core::{
 use attuned_store::StateStore;
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
@@ -18,6 +22,8 @@ use std::time::Instant;
 #[cfg(feature = "inference")]
 use attuned_infer::{Baseline, InferenceConfig, InferenceEngine, InferenceSource};
 #[cfg(feature = "inference")]
+use axum::response::sse::{Event, Sse};
+#[cfg(feature = "inference")]
 use dashmap::DashMap;
 #[cfg(feature = "inference")]
 use std::collections::HashMap;
@@ -30,6 +36,14 @@ pub struct AppState<S: StateStore> {
     pub translator: Arc<dyn Translator>,
     /// Server start time for uptime calculation.
     pub start_time: Instant,
+    /// JWT authentication configuration for per-user scoped endpoints.
+    pub jwt: JwtAuthConfig,
+    /// Static API-key configuration consulted by `/v1/auth/token` when
+    /// exchanging a credential for a session token.
+    pub auth_config: AuthConfig,
+    /// Issues and validates the session/refresh tokens minted by
+    /// `/v1/auth/token`.
+    pub tokens: TokenStore,
     /// Inference engine (optional, requires "inference" feature).
     #[cfg(feature = "inference")]
     pub inference_engine: Option<InferenceEngine>,
@@ -45,6 +59,9 @@ impl<S: StateStore> AppState<S> {
             store: Arc::new(store),
             translator: Arc::new(RuleTranslator::default()),
             start_time: Instant::now(),
+            jwt: JwtAuthConfig::default(),
+            auth_config: AuthConfig::default(),
+            tokens: TokenStore::new(TokenConfig::default()),
             #[cfg(feature = "inference")]
             inference_engine: None,
             #[cfg(feature = "inference")]
@@ -63,14 +80,47 @@ impl<S: StateStore> AppState<S> {
             store: Arc::new(store),
             translator: Arc::new(RuleTranslator::default()),
             start_time: Instant::now(),
+            jwt: JwtAuthConfig::default(),
+            auth_config: AuthConfig::default(),
+            tokens: TokenStore::new(TokenConfig::default()),
             inference_engine: Some(engine),
             baselines: Arc::new(DashMap::new()),
         }
     }
+
+    /// Create application state that accepts `auth_config`'s static API keys
+    /// at `/v1/auth/token` for exchange into short-lived session tokens.
+    pub fn with_auth(store: S, auth_config: AuthConfig) -> Self {
+        Self {
+            auth_config,
+            ..Self::new(store)
+        }
+    }
+
+    /// Resolve the caller's [`Principal`] from the request headers, honoring
+    /// `self.jwt`. Returns `Ok(None)` when JWT auth is disabled.
+    fn authenticate(&self, headers: &HeaderMap) -> Result<Option<Principal>, AuthError> {
+        crate::jwt_auth::resolve_principal(&self.jwt, headers)
+    }
+}
+
+/// Check that an authenticated caller (if any) is permitted to act on `user_id`.
+///
+/// When JWT auth is disabled this is a no-op; when enabled, the token subject
+/// must match `user_id` unless the token carries the `admin` scope.
+pub(crate) fn authorize_path_user<S: StateStore>(
+    state: &AppState<S>,
+    headers: &HeaderMap,
+    user_id: &str,
+) -> Result<(), AuthError> {
+    match state.authenticate(headers)? {
+        Some(principal) => principal.authorize_user(user_id),
+        None => Ok(()),
+    }
 }
 
 /// Request body for upserting state.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct UpsertStateRequest {
     /// User ID to update state for.
     pub user_id: String,
@@ -94,7 +144,7 @@ fn default_confidence() -> f32 {
 }
 
 /// Source of state data in API requests.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SourceInput {
     /// User explicitly provided this state.
@@ -117,7 +167,7 @@ impl From<SourceInput> for Source {
 }
 
 /// Response for state operations.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct StateResponse {
     /// User ID.
     pub user_id: String,
@@ -144,14 +194,14 @@ impl From<StateSnapshot> for StateResponse {
 }
 
 /// Error response format.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ErrorResponse {
     /// Error details.
     pub error: ErrorDetail,
 }
 
 /// Detailed error information.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ErrorDetail {
     /// Error code.
     pub code: String,
@@ -176,12 +226,32 @@ impl ErrorResponse {
 }
 
 /// POST /v1/state - Upsert state
+#[utoipa::path(
+    post,
+    path = "/v1/state",
+    tag = "state",
+    request_body = UpsertStateRequest,
+    responses(
+        (status = 204, description = "State upserted"),
+        (status = 400, description = "VALIDATION_ERROR", body = ErrorResponse),
+        (status = 500, description = "STORE_ERROR", body = ErrorResponse),
+    )
+)]
 #[tracing::instrument(skip(state, body))]
 #[allow(unused_mut)] // mut needed when inference feature is enabled
 pub async fn upsert_state<S: StateStore + 'static>(
     State(state): State<Arc<AppState<S>>>,
-    Json(body): Json<UpsertStateRequest>,
+    headers: HeaderMap,
+    NegotiatedBody(body): NegotiatedBody<UpsertStateRequest>,
 ) -> impl IntoResponse {
+    // When JWT auth is enabled, the token subject is the source of truth for
+    // which user is being written to, regardless of what the body claims.
+    let user_id = match state.authenticate(&headers) {
+        Ok(Some(principal)) => principal.user_id,
+        Ok(None) => body.user_id.clone(),
+        Err(e) => return e.into_response(),
+    };
+
     let mut axes = body.axes;
     let mut source: Source = body.source.into();
 
@@ -191,7 +261,7 @@ pub async fn upsert_state<S: StateStore + 'static>(
         // Get or create baseline for user
         let mut baseline_ref = state
             .baselines
-            .entry(body.user_id.clone())
+            .entry(user_id.clone())
             .or_insert_with(|| engine.new_baseline());
 
         // Run inference with baseline
@@ -212,7 +282,7 @@ pub async fn upsert_state<S: StateStore + 'static>(
     }
 
     let snapshot = match StateSnapshot::builder()
-        .user_id(&body.user_id)
+        .user_id(&user_id)
         .source(source)
         .confidence(body.confidence)
         .axes(axes.into_iter())
@@ -239,13 +309,30 @@ pub async fn upsert_state<S: StateStore + 'static>(
 }
 
 /// GET /v1/state/:user_id - Get state
+#[utoipa::path(
+    get,
+    path = "/v1/state/{user_id}",
+    tag = "state",
+    params(("user_id" = String, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "State found", body = StateResponse),
+        (status = 404, description = "USER_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "STORE_ERROR", body = ErrorResponse),
+    )
+)]
 #[tracing::instrument(skip(state))]
 pub async fn get_state<S: StateStore + 'static>(
     State(state): State<Arc<AppState<S>>>,
+    headers: HeaderMap,
+    encoding: NegotiatedEncoding,
     Path(user_id): Path<String>,
 ) -> impl IntoResponse {
+    if let Err(e) = authorize_path_user(&state, &headers, &user_id) {
+        return e.into_response();
+    }
+
     match state.store.get_latest(&user_id).await {
-        Ok(Some(snapshot)) => Json(StateResponse::from(snapshot)).into_response(),
+        Ok(Some(snapshot)) => Negotiated::new(encoding, StateResponse::from(snapshot)).into_response(),
         Ok(None) => (
             StatusCode::NOT_FOUND,
             Json(ErrorResponse::new(
@@ -263,11 +350,26 @@ pub async fn get_state<S: StateStore + 'static>(
 }
 
 /// DELETE /v1/state/:user_id - Delete state
+#[utoipa::path(
+    delete,
+    path = "/v1/state/{user_id}",
+    tag = "state",
+    params(("user_id" = String, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "State deleted"),
+        (status = 500, description = "STORE_ERROR", body = ErrorResponse),
+    )
+)]
 #[tracing::instrument(skip(state))]
 pub async fn delete_state<S: StateStore + 'static>(
     State(state): State<Arc<AppState<S>>>,
+    headers: HeaderMap,
     Path(user_id): Path<String>,
 ) -> impl IntoResponse {
+    if let Err(e) = authorize_path_user(&state, &headers, &user_id) {
+        return e.into_response();
+    }
+
     match state.store.delete(&user_id).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
         Err(e) => (
@@ -278,16 +380,297 @@ pub async fn delete_state<S: StateStore + 'static>(
     }
 }
 
+/// Request body for `POST /v1/auth/token`.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TokenRequest {
+    /// A static API key, as configured on `AppState::auth_config`.
+    pub credential: String,
+}
+
+/// Response for `POST /v1/auth/token`.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TokenResponse {
+    /// The newly issued session token, used as a bearer credential.
+    pub session_token: String,
+    /// When the session token expires, in Unix milliseconds.
+    pub expires_at_unix_ms: i64,
+}
+
+/// POST /v1/auth/token - Exchange a static API key for a short-lived session token.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/token",
+    tag = "auth",
+    request_body = TokenRequest,
+    responses(
+        (status = 200, description = "Session token issued", body = TokenResponse),
+        (status = 401, description = "INVALID_CREDENTIALS", body = ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(state, body))]
+pub async fn issue_token<S: StateStore + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    Json(body): Json<TokenRequest>,
+) -> impl IntoResponse {
+    if !state.auth_config.validate_key(&body.credential) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse::new(
+                "INVALID_CREDENTIALS",
+                "credential is not a recognized API key",
+            )),
+        )
+            .into_response();
+    }
+
+    let (session_token, expires_at_unix_ms) = state.tokens.issue_session_token(&body.credential);
+    Json(TokenResponse {
+        session_token,
+        expires_at_unix_ms,
+    })
+    .into_response()
+}
+
+/// Request body for batch-upserting state across multiple users.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchUpsertRequest {
+    /// One upsert per user, in the order results are returned.
+    pub items: Vec<UpsertStateRequest>,
+}
+
+/// The outcome of one item within a batch request.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchItemResult {
+    /// User ID this result corresponds to.
+    pub user_id: String,
+    /// Error details, present only if this item failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorDetail>,
+}
+
+/// Response for `POST /v1/state/batch`.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchUpsertResponse {
+    /// Per-item results, in request order.
+    pub results: Vec<BatchItemResult>,
+}
+
+/// POST /v1/state/batch - Upsert state for many users in one request.
+///
+/// Partially-failable: an invalid snapshot for one user doesn't reject the
+/// rest of the batch. Responds `200` if every item succeeded, `207` if at
+/// least one item failed — check `results[].error` either way.
+#[utoipa::path(
+    post,
+    path = "/v1/state/batch",
+    tag = "state",
+    request_body = BatchUpsertRequest,
+    responses(
+        (status = 200, description = "All items upserted", body = BatchUpsertResponse),
+        (status = 207, description = "Some items failed", body = BatchUpsertResponse),
+    )
+)]
+#[tracing::instrument(skip(state, body), fields(count = body.items.len()))]
+pub async fn batch_upsert_state<S: StateStore + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    headers: HeaderMap,
+    NegotiatedBody(body): NegotiatedBody<BatchUpsertRequest>,
+) -> impl IntoResponse {
+    let mut snapshots = Vec::with_capacity(body.items.len());
+    // One slot per request item, so results come back in request order.
+    let mut slots: Vec<Option<BatchItemResult>> = Vec::with_capacity(body.items.len());
+    let mut stored_slots = Vec::new();
+
+    for item in body.items {
+        if let Err(e) = authorize_path_user(&state, &headers, &item.user_id) {
+            slots.push(Some(BatchItemResult {
+                user_id: item.user_id,
+                error: Some(ErrorDetail {
+                    code: "FORBIDDEN".to_string(),
+                    message: e.to_string(),
+                    request_id: None,
+                }),
+            }));
+            continue;
+        }
+
+        match StateSnapshot::builder()
+            .user_id(&item.user_id)
+            .source(item.source.into())
+            .confidence(item.confidence)
+            .axes(item.axes.into_iter())
+            .build()
+        {
+            Ok(snapshot) => {
+                stored_slots.push(slots.len());
+                slots.push(None);
+                snapshots.push(snapshot);
+            }
+            Err(e) => slots.push(Some(BatchItemResult {
+                user_id: item.user_id,
+                error: Some(ErrorDetail {
+                    code: "VALIDATION_ERROR".to_string(),
+                    message: e.to_string(),
+                    request_id: None,
+                }),
+            })),
+        }
+    }
+
+    let user_ids: Vec<String> = snapshots.iter().map(|s| s.user_id.clone()).collect();
+    let store_results = state.store.upsert_many(snapshots).await;
+
+    for ((slot, user_id), result) in stored_slots.into_iter().zip(user_ids).zip(store_results) {
+        slots[slot] = Some(BatchItemResult {
+            error: result.err().map(|e| ErrorDetail {
+                code: "STORE_ERROR".to_string(),
+                message: e.to_string(),
+                request_id: None,
+            }),
+            user_id,
+        });
+    }
+    let results: Vec<BatchItemResult> = slots.into_iter().flatten().collect();
+
+    let status = if results.iter().any(|r| r.error.is_some()) {
+        StatusCode::MULTI_STATUS
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(BatchUpsertResponse { results })).into_response()
+}
+
+/// Request body for batch-querying state across multiple users.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchQueryRequest {
+    /// User IDs to look up, in the order results are returned.
+    pub user_ids: Vec<String>,
+}
+
+/// The outcome of one lookup within a batch query.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchQueryItemResult {
+    /// User ID this result corresponds to.
+    pub user_id: String,
+    /// The user's latest state, or `None` if no state exists for them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<StateResponse>,
+    /// Error details, present only if this lookup failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorDetail>,
+}
+
+/// Response for `POST /v1/state/query`.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BatchQueryResponse {
+    /// Per-item results, in request order.
+    pub results: Vec<BatchQueryItemResult>,
+}
+
+/// POST /v1/state/query - Get state for many users in one request.
+///
+/// Partially-failable: a lookup failure (or authorization failure) for one
+/// user doesn't reject the rest of the batch. Responds `200` if every item
+/// succeeded, `207` if at least one item failed.
+#[utoipa::path(
+    post,
+    path = "/v1/state/query",
+    tag = "state",
+    request_body = BatchQueryRequest,
+    responses(
+        (status = 200, description = "All items resolved", body = BatchQueryResponse),
+        (status = 207, description = "Some items failed", body = BatchQueryResponse),
+    )
+)]
+#[tracing::instrument(skip(state, body), fields(count = body.user_ids.len()))]
+pub async fn batch_query_state<S: StateStore + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    headers: HeaderMap,
+    NegotiatedBody(body): NegotiatedBody<BatchQueryRequest>,
+) -> impl IntoResponse {
+    let mut authorized_ids = Vec::with_capacity(body.user_ids.len());
+    // One slot per requested user id, so results come back in request order.
+    let mut slots: Vec<Option<BatchQueryItemResult>> = Vec::with_capacity(body.user_ids.len());
+    let mut authorized_slots = Vec::new();
+
+    for user_id in body.user_ids {
+        match authorize_path_user(&state, &headers, &user_id) {
+            Ok(()) => {
+                authorized_slots.push(slots.len());
+                slots.push(None);
+                authorized_ids.push(user_id);
+            }
+            Err(e) => slots.push(Some(BatchQueryItemResult {
+                user_id,
+                state: None,
+                error: Some(ErrorDetail {
+                    code: "FORBIDDEN".to_string(),
+                    message: e.to_string(),
+                    request_id: None,
+                }),
+            })),
+        }
+    }
+
+    let store_results = state.store.get_many(&authorized_ids).await;
+    let looked_up = authorized_ids.into_iter().zip(store_results).map(|(user_id, result)| {
+        match result {
+            Ok(snapshot) => BatchQueryItemResult {
+                user_id,
+                state: snapshot.map(StateResponse::from),
+                error: None,
+            },
+            Err(e) => BatchQueryItemResult {
+                user_id,
+                state: None,
+                error: Some(ErrorDetail {
+                    code: "STORE_ERROR".to_string(),
+                    message: e.to_string(),
+                    request_id: None,
+                }),
+            },
+        }
+    });
+    for (slot, item) in authorized_slots.into_iter().zip(looked_up) {
+        slots[slot] = Some(item);
+    }
+    let results: Vec<BatchQueryItemResult> = slots.into_iter().flatten().collect();
+
+    let status = if results.iter().any(|r| r.error.is_some()) {
+        StatusCode::MULTI_STATUS
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(BatchQueryResponse { results })).into_response()
+}
+
 /// GET /v1/context/:user_id - Get translated context
+#[utoipa::path(
+    get,
+    path = "/v1/context/{user_id}",
+    tag = "context",
+    params(("user_id" = String, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Translated context", body = ContextResponse),
+        (status = 404, description = "USER_NOT_FOUND", body = ErrorResponse),
+        (status = 500, description = "STORE_ERROR", body = ErrorResponse),
+    )
+)]
 #[tracing::instrument(skip(state))]
 pub async fn get_context<S: StateStore + 'static>(
     State(state): State<Arc<AppState<S>>>,
+    headers: HeaderMap,
+    encoding: NegotiatedEncoding,
     Path(user_id): Path<String>,
 ) -> impl IntoResponse {
+    if let Err(e) = authorize_path_user(&state, &headers, &user_id) {
+        return e.into_response();
+    }
+
     match state.store.get_latest(&user_id).await {
         Ok(Some(snapshot)) => {
-            let context = state.translator.to_prompt_context(&snapshot);
-            Json(context).into_response()
+            let context = ContextResponse::from(state.translator.to_prompt_context(&snapshot));
+            Negotiated::new(encoding, context).into_response()
         }
         Ok(None) => (
             StatusCode::NOT_FOUND,
@@ -306,7 +689,7 @@ pub async fn get_context<S: StateStore + 'static>(
 }
 
 /// Request body for inline translation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct TranslateRequest {
     /// Axis values to translate.
     pub axes: std::collections::BTreeMap<String, f32>,
@@ -319,10 +702,21 @@ pub struct TranslateRequest {
 }
 
 /// POST /v1/translate - Translate arbitrary state
+#[utoipa::path(
+    post,
+    path = "/v1/translate",
+    tag = "context",
+    request_body = TranslateRequest,
+    responses(
+        (status = 200, description = "Translated context", body = ContextResponse),
+        (status = 400, description = "VALIDATION_ERROR", body = ErrorResponse),
+    )
+)]
 #[tracing::instrument(skip(state, body))]
 pub async fn translate<S: StateStore + 'static>(
     State(state): State<Arc<AppState<S>>>,
-    Json(body): Json<TranslateRequest>,
+    encoding: NegotiatedEncoding,
+    NegotiatedBody(body): NegotiatedBody<TranslateRequest>,
 ) -> impl IntoResponse {
     let snapshot = match StateSnapshot::builder()
         .user_id("_anonymous")
@@ -341,11 +735,17 @@ pub async fn translate<S: StateStore + 'static>(
         }
     };
 
-    let context = state.translator.to_prompt_context(&snapshot);
-    Json(context).into_response()
+    let context = ContextResponse::from(state.translator.to_prompt_context(&snapshot));
+    Negotiated::new(encoding, context).into_response()
 }
 
 /// GET /health - Health check
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "ops",
+    responses((status = 200, description = "Health status"), (status = 503, description = "Unhealthy"))
+)]
 #[tracing::instrument(skip(state))]
 pub async fn health<S: StateStore + HealthCheck + 'static>(
     State(state): State<Arc<AppState<S>>>,
@@ -365,6 +765,12 @@ pub async fn health<S: StateStore + HealthCheck + 'static>(
 }
 
 /// GET /ready - Readiness check
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "ops",
+    responses((status = 200, description = "Ready"), (status = 503, description = "Not ready"))
+)]
 #[tracing::instrument(skip(state))]
 pub async fn ready<S: StateStore + 'static>(
     State(state): State<Arc<AppState<S>>>,
@@ -376,7 +782,7 @@ pub async fn ready<S: StateStore + 'static>(
 }
 
 /// Response for prompt context.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ContextResponse {
     /// Behavioral guidelines for the LLM.
     pub guidelines: Vec<String>,
@@ -405,7 +811,7 @@ impl From<PromptContext> for ContextResponse {
 
 /// Request body for inference endpoint.
 #[cfg(feature = "inference")]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct InferRequest {
     /// The message text to analyze.
     pub message: String,
@@ -420,7 +826,7 @@ pub struct InferRequest {
 
 /// A single axis estimate in the inference response.
 #[cfg(feature = "inference")]
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct InferEstimate {
     /// The axis name.
     pub axis: String,
@@ -434,7 +840,7 @@ pub struct InferEstimate {
 
 /// Inference source for API response.
 #[cfg(feature = "inference")]
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum InferSourceResponse {
     /// Inferred from linguistic features.
@@ -496,22 +902,41 @@ impl From<&InferenceSource> for InferSourceResponse {
 
 /// Response for inference endpoint.
 #[cfg(feature = "inference")]
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct InferResponse {
     /// Estimated axes.
     pub estimates: Vec<InferEstimate>,
     /// Debug feature information (if requested).
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object)]
     pub features: Option<HashMap<String, serde_json::Value>>,
 }
 
 /// POST /v1/infer - Infer axes from message text without storage
 #[cfg(feature = "inference")]
+#[utoipa::path(
+    post,
+    path = "/v1/infer",
+    tag = "inference",
+    request_body = InferRequest,
+    responses(
+        (status = 200, description = "Inferred axes", body = InferResponse),
+        (status = 503, description = "INFERENCE_DISABLED", body = ErrorResponse),
+    )
+)]
 #[tracing::instrument(skip(state, body))]
 pub async fn infer<S: StateStore + 'static>(
     State(state): State<Arc<AppState<S>>>,
-    Json(body): Json<InferRequest>,
+    headers: HeaderMap,
+    encoding: NegotiatedEncoding,
+    NegotiatedBody(body): NegotiatedBody<InferRequest>,
 ) -> impl IntoResponse {
+    if let Some(user_id) = &body.user_id {
+        if let Err(e) = authorize_path_user(&state, &headers, user_id) {
+            return e.into_response();
+        }
+    }
+
     let Some(engine) = &state.inference_engine else {
         return (
             StatusCode::SERVICE_UNAVAILABLE,
@@ -576,9 +1001,93 @@ pub async fn infer<S: StateStore + 'static>(
         None
     };
 
-    Json(InferResponse {
-        estimates,
-        features,
-    })
+    Negotiated::new(
+        encoding,
+        InferResponse {
+            estimates,
+            features,
+        },
+    )
     .into_response()
 }
+
+/// POST /v1/infer/stream - Infer axes from message text, streaming estimates
+/// as Server-Sent Events instead of waiting for the full batch.
+///
+/// Emits one `event: estimate` per inferred axis, followed by a terminal
+/// `event: done`. Yielding between chunks (rather than computing and writing
+/// the whole response in one go) gives the runtime a point to notice the
+/// response body was dropped, so a disconnected client cancels the stream
+/// instead of leaking the in-flight inference.
+#[cfg(feature = "inference")]
+#[utoipa::path(
+    post,
+    path = "/v1/infer/stream",
+    tag = "inference",
+    request_body = InferRequest,
+    responses(
+        (status = 200, description = "Server-sent stream of inferred axes (text/event-stream)"),
+        (status = 503, description = "INFERENCE_DISABLED", body = ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(state, body))]
+pub async fn infer_stream<S: StateStore + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    headers: HeaderMap,
+    NegotiatedBody(body): NegotiatedBody<InferRequest>,
+) -> impl IntoResponse {
+    if let Some(user_id) = &body.user_id {
+        if let Err(e) = authorize_path_user(&state, &headers, user_id) {
+            return e.into_response();
+        }
+    }
+
+    if state.inference_engine.is_none() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "INFERENCE_DISABLED",
+                "Inference is not enabled on this server",
+            )),
+        )
+            .into_response();
+    }
+
+    let stream = async_stream::stream! {
+        let engine = state
+            .inference_engine
+            .as_ref()
+            .expect("checked for Some above");
+
+        let inferred = if let Some(user_id) = &body.user_id {
+            let mut baseline_ref = state
+                .baselines
+                .entry(user_id.clone())
+                .or_insert_with(|| engine.new_baseline());
+            engine.infer_with_baseline(&body.message, &mut baseline_ref, None)
+        } else {
+            engine.infer(&body.message)
+        };
+
+        for est in inferred.all() {
+            let estimate = InferEstimate {
+                axis: est.axis.clone(),
+                value: est.value,
+                confidence: est.confidence,
+                source: InferSourceResponse::from(&est.source),
+            };
+            let payload = serde_json::to_string(&estimate).unwrap_or_else(|_| "{}".to_string());
+            yield Ok::<_, std::convert::Infallible>(Event::default().event("estimate").data(payload));
+
+            // Cooperative yield point: gives axum a chance to notice a
+            // dropped response body and stop polling this stream.
+            tokio::task::yield_now().await;
+        }
+
+        yield Ok(Event::default().event("done").data("{}"));
+    };
+
+    Sse::new(stream)
+        .keep_alive(axum::response::sse::KeepAlive::default())
+        .into_response()
+}
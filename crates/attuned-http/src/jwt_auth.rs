@@ -0,0 +1,240 @@
+//! JWT-based authentication for per-user scoped endpoints.
+//!
+//! This is distinct from [`crate::middleware::AuthConfig`], which gates the
+//! whole API behind a static set of API keys. This module decodes a bearer
+//! JWT into a [`Principal`] (subject + scopes) so handlers can assert that
+//! the caller is only touching their own data.
+
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::{request::Parts, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::handlers::ErrorResponse;
+
+/// Scope granting access to every user's state, bypassing subject matching.
+pub const ADMIN_SCOPE: &str = "admin";
+
+/// Configuration for validating bearer JWTs.
+#[derive(Clone, Debug)]
+pub struct JwtAuthConfig {
+    /// Whether JWT auth is enforced at all.
+    pub enabled: bool,
+    /// Secret (or PEM key, depending on `algorithm`) used to verify signatures.
+    pub secret: Vec<u8>,
+    /// Signing algorithm the tokens are expected to use.
+    pub algorithm: Algorithm,
+}
+
+impl Default for JwtAuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            secret: Vec::new(),
+            algorithm: Algorithm::HS256,
+        }
+    }
+}
+
+impl JwtAuthConfig {
+    /// Create a config that verifies HS256 tokens with the given shared secret.
+    pub fn hs256(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            enabled: true,
+            secret: secret.into(),
+            algorithm: Algorithm::HS256,
+        }
+    }
+
+    fn decoding_key(&self) -> Result<DecodingKey, AuthError> {
+        let key = match self.algorithm {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                return Ok(DecodingKey::from_secret(&self.secret));
+            }
+            Algorithm::RS256
+            | Algorithm::RS384
+            | Algorithm::RS512
+            | Algorithm::PS256
+            | Algorithm::PS384
+            | Algorithm::PS512 => DecodingKey::from_rsa_pem(&self.secret),
+            Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(&self.secret),
+            _ => DecodingKey::from_ed_pem(&self.secret),
+        };
+        key.map_err(|_| AuthError::InvalidToken)
+    }
+}
+
+/// Claims carried by an Attuned bearer token.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Claims {
+    /// Subject: the user id this token was issued for.
+    pub sub: String,
+    /// Expiry, as Unix seconds.
+    pub exp: i64,
+    /// Scopes granted to this token (e.g. `"admin"`).
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+/// An authenticated caller, resolved from a validated JWT.
+#[derive(Clone, Debug)]
+pub struct Principal {
+    /// The user id encoded as the token subject.
+    pub user_id: String,
+    /// Scopes granted to this token.
+    pub scopes: Vec<String>,
+}
+
+impl Principal {
+    /// Whether this principal holds the admin scope, which bypasses
+    /// per-user subject matching.
+    pub fn is_admin(&self) -> bool {
+        self.scopes.iter().any(|s| s == ADMIN_SCOPE)
+    }
+
+    /// Assert that this principal is allowed to act on `user_id`.
+    pub fn authorize_user(&self, user_id: &str) -> Result<(), AuthError> {
+        if self.is_admin() || self.user_id == user_id {
+            Ok(())
+        } else {
+            Err(AuthError::Forbidden)
+        }
+    }
+}
+
+/// Errors that can occur while authenticating a request.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum AuthError {
+    /// No `Authorization` header (or no bearer token) was present.
+    #[error("missing credentials")]
+    MissingCredentials,
+
+    /// The token failed to parse or its signature did not verify.
+    #[error("invalid token")]
+    InvalidToken,
+
+    /// The token's `exp` claim is in the past.
+    #[error("token expired")]
+    Expired,
+
+    /// The token is valid but its subject/scopes don't permit this action.
+    #[error("forbidden")]
+    Forbidden,
+}
+
+impl AuthError {
+    fn status(&self) -> StatusCode {
+        match self {
+            AuthError::MissingCredentials | AuthError::InvalidToken | AuthError::Expired => {
+                StatusCode::UNAUTHORIZED
+            }
+            AuthError::Forbidden => StatusCode::FORBIDDEN,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            AuthError::MissingCredentials => "MISSING_CREDENTIALS",
+            AuthError::InvalidToken => "INVALID_TOKEN",
+            AuthError::Expired => "TOKEN_EXPIRED",
+            AuthError::Forbidden => "FORBIDDEN",
+        }
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let body = ErrorResponse::new(self.code(), &self.to_string());
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Resolve the caller's [`Principal`] from request headers according to `config`.
+///
+/// Returns `Ok(None)` when JWT auth is disabled entirely, so callers can
+/// distinguish "no auth configured" from "auth configured but missing".
+pub fn resolve_principal(
+    config: &JwtAuthConfig,
+    headers: &axum::http::HeaderMap,
+) -> Result<Option<Principal>, AuthError> {
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let header = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+
+    authenticate(config, header).map(Some)
+}
+
+/// Decode and validate a bearer token against `config`, returning the
+/// resulting [`Principal`].
+pub fn authenticate(config: &JwtAuthConfig, authorization_header: Option<&str>) -> Result<Principal, AuthError> {
+    let header = authorization_header.ok_or(AuthError::MissingCredentials)?;
+    let token = header
+        .strip_prefix("Bearer ")
+        .ok_or(AuthError::MissingCredentials)?;
+
+    let mut validation = Validation::new(config.algorithm);
+    validation.validate_exp = true;
+
+    let data = decode::<Claims>(token, &config.decoding_key()?, &validation).map_err(|e| {
+        use jsonwebtoken::errors::ErrorKind;
+        match e.kind() {
+            ErrorKind::ExpiredSignature => AuthError::Expired,
+            _ => AuthError::InvalidToken,
+        }
+    })?;
+
+    Ok(Principal {
+        user_id: data.claims.sub,
+        scopes: data.claims.scopes,
+    })
+}
+
+/// State for the JWT authentication middleware.
+#[derive(Clone)]
+pub struct JwtAuthState {
+    /// The JWT validation configuration.
+    pub config: Arc<JwtAuthConfig>,
+}
+
+/// Middleware that validates the bearer JWT and injects the resulting
+/// [`Principal`] into request extensions for downstream extractors/handlers.
+pub async fn jwt_auth(
+    State(state): State<JwtAuthState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    if let Some(principal) = resolve_principal(&state.config, request.headers())? {
+        request.extensions_mut().insert(principal);
+    }
+    Ok(next.run(request).await)
+}
+
+/// Extractor that pulls the [`Principal`] injected by the JWT middleware
+/// out of request extensions.
+impl<S> FromRequestParts<S> for Principal
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Principal>()
+            .cloned()
+            .ok_or(AuthError::MissingCredentials)
+    }
+}
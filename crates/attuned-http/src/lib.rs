@@ -13,6 +13,8 @@
 //! - `DELETE /v1/state/{user_id}` - Delete state
 //! - `GET /health` - Health check
 //! - `GET /metrics` - Prometheus metrics
+//! - `GET /openapi.json` - OpenAPI 3 spec
+//! - `GET /docs` - Interactive Swagger UI
 //!
 //! ## Example
 //!
@@ -35,14 +37,35 @@
 
 // TODO: Implement HTTP server (TASK-006)
 
+pub mod api_key;
+pub mod backend;
+pub mod content;
 mod config;
+pub mod cors;
 mod error;
 pub mod handlers;
+pub mod jwt_auth;
 pub mod middleware;
+pub mod openapi;
+pub mod rate_limit;
 mod server;
+pub mod tools;
+pub mod tokens;
 
+pub use api_key::{ApiKey, ApiKeyRecord, ParseApiKeyError, StructuredKeyAuthenticator};
+pub use backend::{BackendError, BoxedStore, DynStore};
 pub use config::ServerConfig;
+pub use content::{Negotiated, NegotiatedBody, NegotiatedEncoding};
+pub use cors::{CorsConfig, CorsOrigins};
 pub use error::HttpError;
 pub use handlers::AppState;
-pub use middleware::{AuthConfig, RateLimitConfig, RateLimitKey};
+pub use jwt_auth::{AuthError, Claims, JwtAuthConfig, Principal};
+pub use middleware::{
+    api_key_auth, AuthConfig, AuthState, Authenticator, AuthenticationError,
+    ConcurrencyLimitConfig, ConcurrencyLimitState, Identity, RateLimitConfig, RateLimitKey,
+    StaticKeyAuthenticator,
+};
+pub use rate_limit::{InMemoryBackend, RateLimitBackend, RateLimitDecision, RedisBackend};
+pub use tokens::{TokenAuthenticator, TokenConfig, TokenError, TokenKind, TokenStore};
+pub use tools::{InvokeToolRequest, ToolError, ToolSchema};
 pub use server::Server;
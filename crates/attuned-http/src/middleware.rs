@@ -1,16 +1,17 @@
 //! HTTP middleware for security, rate limiting, and authentication.
 
+use crate::rate_limit::{InMemoryBackend, RateLimitBackend, RateLimitDecision};
+use async_trait::async_trait;
 use axum::{
     extract::{ConnectInfo, Request, State},
     http::{header, HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use std::time::Duration;
 
 // ============================================================================
 // Security Headers Middleware
@@ -97,7 +98,10 @@ impl Default for AuthConfig {
             api_keys: HashSet::new(),
             header_name: "Authorization".to_string(),
             prefix: "Bearer ".to_string(),
-            public_paths: ["/health", "/ready"].iter().map(|s| s.to_string()).collect(),
+            public_paths: ["/health", "/ready", "/v1/auth/token"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
         }
     }
 }
@@ -133,72 +137,167 @@ impl AuthConfig {
     }
 }
 
+/// The resolved identity of an authenticated caller.
+///
+/// Downstream handlers and the rate limiter consume this instead of an
+/// opaque API key, so quota/tier decisions can depend on *who* is calling.
+#[derive(Clone, Debug)]
+pub struct Identity {
+    /// Stable identifier for the caller (e.g. the key's owner id).
+    pub user_id: String,
+    /// Rate-limit / feature tier this caller belongs to.
+    pub tier: String,
+    /// Scopes granted to this caller.
+    pub scopes: Vec<String>,
+}
+
+/// An error produced while resolving an [`Identity`] from a request.
+#[derive(Debug, Clone, thiserror::Error)]
+#[non_exhaustive]
+pub enum AuthenticationError {
+    /// No credentials were supplied.
+    #[error("missing authorization header")]
+    MissingCredentials,
+    /// Credentials were supplied but rejected.
+    #[error("invalid credentials")]
+    InvalidCredentials,
+    /// Credentials were valid but have since expired (e.g. a session token
+    /// past its TTL). Kept distinct from `InvalidCredentials` so clients
+    /// know to re-authenticate rather than that the token was malformed.
+    #[error("credentials expired")]
+    Expired,
+}
+
+impl IntoResponse for AuthenticationError {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            self.to_string(),
+        )
+            .into_response()
+    }
+}
+
+/// Pluggable source of truth for "who is making this request".
+///
+/// The static API-key set ([`StaticKeyAuthenticator`]) is the default, but
+/// this trait lets callers plug in database-backed keys, JWT validation, or
+/// mTLS without forking the middleware.
+#[async_trait]
+pub trait Authenticator: Send + Sync {
+    /// Resolve the caller's identity from the request, or reject it.
+    ///
+    /// `path` is passed separately so implementations can apply path-based
+    /// exemptions (e.g. `/health`) without re-parsing the request URI.
+    async fn authenticate(
+        &self,
+        headers: &axum::http::HeaderMap,
+        path: &str,
+    ) -> Result<Identity, AuthenticationError>;
+}
+
+/// [`Authenticator`] backed by a static set of bearer API keys, matching the
+/// crate's original behavior.
+pub struct StaticKeyAuthenticator {
+    config: AuthConfig,
+}
+
+impl StaticKeyAuthenticator {
+    /// Build an authenticator from the given static-key configuration.
+    pub fn new(config: AuthConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Authenticator for StaticKeyAuthenticator {
+    async fn authenticate(
+        &self,
+        headers: &axum::http::HeaderMap,
+        path: &str,
+    ) -> Result<Identity, AuthenticationError> {
+        if !self.config.requires_auth(path) || !self.config.is_enabled() {
+            // No auth configured/required: resolve an anonymous identity
+            // rather than failing closed.
+            return Ok(Identity {
+                user_id: "anonymous".to_string(),
+                tier: "anonymous".to_string(),
+                scopes: vec![],
+            });
+        }
+
+        let auth_header = headers
+            .get(&self.config.header_name)
+            .and_then(|v| v.to_str().ok());
+
+        let api_key = match auth_header {
+            Some(value) if value.starts_with(&self.config.prefix) => &value[self.config.prefix.len()..],
+            Some(_) => return Err(AuthenticationError::InvalidCredentials),
+            None => return Err(AuthenticationError::MissingCredentials),
+        };
+
+        if !self.config.validate_key(api_key) {
+            tracing::warn!(path = %path, "Invalid API key attempt");
+            return Err(AuthenticationError::InvalidCredentials);
+        }
+
+        Ok(Identity {
+            user_id: api_key.to_string(),
+            tier: "default".to_string(),
+            scopes: vec![],
+        })
+    }
+}
+
 /// State for authentication middleware.
 #[derive(Clone)]
 pub struct AuthState {
-    /// The authentication configuration.
+    /// The authentication configuration (kept for backwards compatibility
+    /// with callers that only want the static-key behavior).
     pub config: Arc<AuthConfig>,
+    /// The pluggable authenticator actually consulted by [`api_key_auth`].
+    pub authenticator: Arc<dyn Authenticator>,
+}
+
+impl AuthState {
+    /// Build auth state using the default [`StaticKeyAuthenticator`].
+    pub fn new(config: AuthConfig) -> Self {
+        let authenticator = Arc::new(StaticKeyAuthenticator::new(config.clone()));
+        Self {
+            config: Arc::new(config),
+            authenticator,
+        }
+    }
+
+    /// Build auth state with a custom [`Authenticator`] implementation.
+    pub fn with_authenticator(config: AuthConfig, authenticator: Arc<dyn Authenticator>) -> Self {
+        Self {
+            config: Arc::new(config),
+            authenticator,
+        }
+    }
 }
 
 /// API key authentication middleware.
+///
+/// Delegates to `auth.authenticator` and, on success, inserts the resolved
+/// [`Identity`] into request extensions for downstream handlers and the
+/// rate limiter to consume.
 pub async fn api_key_auth(
     State(auth): State<AuthState>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Result<Response, Response> {
-    let path = request.uri().path();
+    let path = request.uri().path().to_string();
 
-    // Skip auth for public paths
-    if !auth.config.requires_auth(path) {
-        return Ok(next.run(request).await);
-    }
-
-    // Skip auth if not enabled (no keys configured)
-    if !auth.config.is_enabled() {
-        return Ok(next.run(request).await);
-    }
+    let identity = auth
+        .authenticator
+        .authenticate(request.headers(), &path)
+        .await
+        .map_err(IntoResponse::into_response)?;
 
-    // Extract API key from header
-    let auth_header = request
-        .headers()
-        .get(&auth.config.header_name)
-        .and_then(|v| v.to_str().ok());
-
-    let api_key = match auth_header {
-        Some(value) if value.starts_with(&auth.config.prefix) => {
-            &value[auth.config.prefix.len()..]
-        }
-        Some(_) => {
-            return Err((
-                StatusCode::UNAUTHORIZED,
-                [(header::WWW_AUTHENTICATE, "Bearer")],
-                "Invalid authorization header format",
-            )
-                .into_response());
-        }
-        None => {
-            return Err((
-                StatusCode::UNAUTHORIZED,
-                [(header::WWW_AUTHENTICATE, "Bearer")],
-                "Missing authorization header",
-            )
-                .into_response());
-        }
-    };
-
-    // Validate the key
-    if !auth.config.validate_key(api_key) {
-        tracing::warn!(
-            path = %path,
-            "Invalid API key attempt"
-        );
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            [(header::WWW_AUTHENTICATE, "Bearer")],
-            "Invalid API key",
-        )
-            .into_response());
-    }
+    request.extensions_mut().insert(identity);
 
     Ok(next.run(request).await)
 }
@@ -238,94 +337,113 @@ impl Default for RateLimitConfig {
     }
 }
 
-/// Entry in the rate limit store.
-#[derive(Clone)]
-struct RateLimitEntry {
-    count: u32,
-    window_start: Instant,
-}
-
 /// State for rate limiting middleware.
 #[derive(Clone)]
 pub struct RateLimitState {
-    /// The rate limiting configuration.
+    /// The rate limiting configuration used when no per-tier override
+    /// applies (or when the caller has no resolved [`Identity`]).
     pub config: Arc<RateLimitConfig>,
-    entries: Arc<RwLock<std::collections::HashMap<String, RateLimitEntry>>>,
+    /// Per-tier overrides, keyed by [`Identity::tier`]. Looked up only when
+    /// `config.key_strategy` is [`RateLimitKey::ByApiKey`].
+    tiers: Arc<HashMap<String, RateLimitConfig>>,
+    /// The backend tracking request counts (in-process by default; swap in
+    /// [`crate::rate_limit::RedisBackend`] to share limits across replicas).
+    backend: Arc<dyn RateLimitBackend>,
 }
 
 impl RateLimitState {
-    /// Create a new rate limit state.
+    /// Create a new rate limit state backed by the default in-memory backend.
     pub fn new(config: RateLimitConfig) -> Self {
+        Self::with_backend(config, Arc::new(InMemoryBackend::new()))
+    }
+
+    /// Create a new rate limit state backed by a custom [`RateLimitBackend`]
+    /// (e.g. a Redis-backed one shared across replicas).
+    pub fn with_backend(config: RateLimitConfig, backend: Arc<dyn RateLimitBackend>) -> Self {
         Self {
             config: Arc::new(config),
-            entries: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            tiers: Arc::new(HashMap::new()),
+            backend,
         }
     }
 
-    /// Clean up expired entries.
-    pub async fn cleanup(&self) {
-        let now = Instant::now();
-        let window = self.config.window;
-        let mut entries = self.entries.write().await;
-        entries.retain(|_, entry| now.duration_since(entry.window_start) < window);
+    /// Attach per-tier [`RateLimitConfig`] overrides, keyed by tier name.
+    ///
+    /// Used with [`RateLimitKey::ByApiKey`] so a structured key's tier (see
+    /// `attuned_http::api_key`) picks its own `max_requests`/`window` instead
+    /// of the single global config.
+    pub fn with_tiers(mut self, tiers: HashMap<String, RateLimitConfig>) -> Self {
+        self.tiers = Arc::new(tiers);
+        self
     }
 
-    /// Check and increment rate limit for a key.
-    async fn check_and_increment(&self, key: String) -> Result<(u32, u32), (u32, Duration)> {
-        let now = Instant::now();
-        let mut entries = self.entries.write().await;
-
-        let entry = entries.entry(key).or_insert_with(|| RateLimitEntry {
-            count: 0,
-            window_start: now,
-        });
-
-        // Reset if window has passed
-        if now.duration_since(entry.window_start) >= self.config.window {
-            entry.count = 0;
-            entry.window_start = now;
+    /// Resolve the effective config for an (optional) caller identity.
+    fn config_for(&self, identity: Option<&Identity>) -> &RateLimitConfig {
+        if !matches!(self.config.key_strategy, RateLimitKey::ByApiKey) {
+            return &self.config;
         }
+        identity
+            .and_then(|identity| self.tiers.get(&identity.tier))
+            .unwrap_or(&self.config)
+    }
 
-        entry.count += 1;
+    async fn check(&self, key: String, config: &RateLimitConfig) -> RateLimitDecision {
+        self.backend.check(&key, config.max_requests, config.window).await
+    }
 
-        if entry.count > self.config.max_requests {
-            let retry_after = self.config.window - now.duration_since(entry.window_start);
-            Err((entry.count, retry_after))
-        } else {
-            Ok((self.config.max_requests - entry.count, self.config.max_requests))
+    /// Periodically evict rate-limit entries idle longer than `config`'s
+    /// window, so a churning key space (e.g. `ByIp` behind many distinct
+    /// clients) doesn't grow the backend's table unbounded. Runs until
+    /// cancelled — pair with `tokio::select!`/`JoinHandle::abort` at shutdown.
+    pub async fn run_sweeper(&self) {
+        let mut interval = tokio::time::interval(self.config.window);
+        loop {
+            interval.tick().await;
+            self.backend.cleanup(self.config.window).await;
         }
     }
 }
 
 /// Rate limiting middleware.
+///
+/// When `key_strategy` is [`RateLimitKey::ByApiKey`] and an [`Identity`] was
+/// inserted into request extensions by [`api_key_auth`] (which must run
+/// earlier in the stack), the caller's tier picks its own [`RateLimitConfig`]
+/// out of `state`'s per-tier overrides; otherwise the global config applies.
 pub async fn rate_limit(
     State(state): State<RateLimitState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     request: Request,
     next: Next,
 ) -> Result<Response, Response> {
+    let identity = request.extensions().get::<Identity>().cloned();
+
     let key = match state.config.key_strategy {
         RateLimitKey::ByIp => addr.ip().to_string(),
-        RateLimitKey::ByApiKey => {
-            // Extract API key from Authorization header
-            request
-                .headers()
-                .get(header::AUTHORIZATION)
-                .and_then(|v| v.to_str().ok())
-                .map(|s| s.trim_start_matches("Bearer ").to_string())
-                .unwrap_or_else(|| addr.ip().to_string())
-        }
+        RateLimitKey::ByApiKey => identity
+            .as_ref()
+            .map(|identity| identity.user_id.clone())
+            .or_else(|| {
+                request
+                    .headers()
+                    .get(header::AUTHORIZATION)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|s| s.trim_start_matches("Bearer ").to_string())
+            })
+            .unwrap_or_else(|| addr.ip().to_string()),
     };
 
-    match state.check_and_increment(key).await {
-        Ok((remaining, limit)) => {
+    let config = state.config_for(identity.as_ref()).clone();
+
+    match state.check(key, &config).await {
+        RateLimitDecision::Allowed { remaining } => {
             let mut response = next.run(request).await;
             let headers = response.headers_mut();
 
             // Add rate limit headers
             headers.insert(
                 "X-RateLimit-Limit",
-                HeaderValue::from_str(&limit.to_string()).unwrap(),
+                HeaderValue::from_str(&config.max_requests.to_string()).unwrap(),
             );
             headers.insert(
                 "X-RateLimit-Remaining",
@@ -334,22 +452,142 @@ pub async fn rate_limit(
 
             Ok(response)
         }
-        Err((_, retry_after)) => {
+        RateLimitDecision::RetryAt { retry_after } => {
             let retry_secs = retry_after.as_secs().max(1);
             Err((
                 StatusCode::TOO_MANY_REQUESTS,
                 [
                     ("Retry-After", retry_secs.to_string()),
-                    ("X-RateLimit-Limit", state.config.max_requests.to_string()),
+                    ("X-RateLimit-Limit", config.max_requests.to_string()),
                     ("X-RateLimit-Remaining", "0".to_string()),
                 ],
                 "Rate limit exceeded",
             )
                 .into_response())
         }
+        RateLimitDecision::Denied => Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            [
+                ("X-RateLimit-Limit", config.max_requests.to_string()),
+                ("X-RateLimit-Remaining", "0".to_string()),
+            ],
+            "Rate limit exceeded",
+        )
+            .into_response()),
     }
 }
 
+// ============================================================================
+// Concurrency Limit Middleware
+// ============================================================================
+
+/// Configuration for the concurrency-limit middleware.
+///
+/// Unlike [`RateLimitConfig`], which bounds requests *per window*, this
+/// bounds requests *in flight at once* per client — protecting expensive
+/// handlers (translate, context) from a single client saturating the server
+/// even while staying under the request-rate limit.
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimitConfig {
+    /// Maximum number of concurrent in-flight requests per client. `0`
+    /// blocks all requests from that client.
+    pub max_concurrent_requests: usize,
+    /// How to identify clients, shared with [`RateLimitKey`] so both
+    /// middlewares key off the same notion of "client".
+    pub key_strategy: RateLimitKey,
+}
+
+impl Default for ConcurrencyLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 10,
+            key_strategy: RateLimitKey::ByIp,
+        }
+    }
+}
+
+/// State for the concurrency-limit middleware.
+///
+/// Holds one [`tokio::sync::Semaphore`] per client key, created lazily on
+/// first use. Semaphores are never removed, so long-lived deployments with
+/// unbounded key cardinality (e.g. `ByIp` behind a churning client pool)
+/// should prefer [`RateLimitKey::ByApiKey`] to bound the map's size.
+#[derive(Clone)]
+pub struct ConcurrencyLimitState {
+    config: Arc<ConcurrencyLimitConfig>,
+    semaphores: Arc<dashmap::DashMap<String, Arc<tokio::sync::Semaphore>>>,
+}
+
+impl ConcurrencyLimitState {
+    /// Create a new concurrency-limit state from the given config.
+    pub fn new(config: ConcurrencyLimitConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            semaphores: Arc::new(dashmap::DashMap::new()),
+        }
+    }
+
+    fn semaphore_for(&self, key: &str) -> Arc<tokio::sync::Semaphore> {
+        self.semaphores
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(tokio::sync::Semaphore::new(self.config.max_concurrent_requests)))
+            .clone()
+    }
+}
+
+/// Concurrency-limit middleware.
+///
+/// Acquires an owned permit from the client's semaphore before running the
+/// request, holding it until the response is produced, and returns `503`
+/// with `Retry-After` when no permit is immediately available.
+pub async fn concurrency_limit(
+    State(state): State<ConcurrencyLimitState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Result<Response, Response> {
+    if state.config.max_concurrent_requests == 0 {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            [("Retry-After", "1")],
+            "Concurrency limit reached",
+        )
+            .into_response());
+    }
+
+    let identity = request.extensions().get::<Identity>().cloned();
+
+    let key = match state.config.key_strategy {
+        RateLimitKey::ByIp => addr.ip().to_string(),
+        RateLimitKey::ByApiKey => identity
+            .map(|identity| identity.user_id)
+            .or_else(|| {
+                request
+                    .headers()
+                    .get(header::AUTHORIZATION)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|s| s.trim_start_matches("Bearer ").to_string())
+            })
+            .unwrap_or_else(|| addr.ip().to_string()),
+    };
+
+    let semaphore = state.semaphore_for(&key);
+
+    let _permit = match semaphore.try_acquire_owned() {
+        Ok(permit) => permit,
+        Err(_) => {
+            return Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                [("Retry-After", "1")],
+                "Too many concurrent requests",
+            )
+                .into_response());
+        }
+    };
+
+    Ok(next.run(request).await)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -389,21 +627,85 @@ mod tests {
 
     #[tokio::test]
     async fn test_rate_limit_state() {
-        let state = RateLimitState::new(RateLimitConfig {
+        let config = RateLimitConfig {
             max_requests: 3,
             window: Duration::from_secs(60),
             key_strategy: RateLimitKey::ByIp,
-        });
+        };
+        let state = RateLimitState::new(config.clone());
 
         // First 3 requests should succeed
-        assert!(state.check_and_increment("test".to_string()).await.is_ok());
-        assert!(state.check_and_increment("test".to_string()).await.is_ok());
-        assert!(state.check_and_increment("test".to_string()).await.is_ok());
+        assert!(matches!(
+            state.check("test".to_string(), &config).await,
+            RateLimitDecision::Allowed { .. }
+        ));
+        assert!(matches!(
+            state.check("test".to_string(), &config).await,
+            RateLimitDecision::Allowed { .. }
+        ));
+        assert!(matches!(
+            state.check("test".to_string(), &config).await,
+            RateLimitDecision::Allowed { .. }
+        ));
 
         // 4th should fail
-        assert!(state.check_and_increment("test".to_string()).await.is_err());
+        assert!(matches!(
+            state.check("test".to_string(), &config).await,
+            RateLimitDecision::RetryAt { .. }
+        ));
 
         // Different key should succeed
-        assert!(state.check_and_increment("other".to_string()).await.is_ok());
+        assert!(matches!(
+            state.check("other".to_string(), &config).await,
+            RateLimitDecision::Allowed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_state_per_tier_override() {
+        let global = RateLimitConfig {
+            max_requests: 1,
+            window: Duration::from_secs(60),
+            key_strategy: RateLimitKey::ByApiKey,
+        };
+        let gold = RateLimitConfig {
+            max_requests: 10,
+            window: Duration::from_secs(60),
+            key_strategy: RateLimitKey::ByApiKey,
+        };
+        let state = RateLimitState::new(global)
+            .with_tiers(HashMap::from([("gold".to_string(), gold.clone())]));
+
+        let identity = Identity {
+            user_id: "user-1".to_string(),
+            tier: "gold".to_string(),
+            scopes: vec![],
+        };
+
+        assert_eq!(state.config_for(Some(&identity)).max_requests, gold.max_requests);
+        assert_eq!(state.config_for(None).max_requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_state_saturates() {
+        let state = ConcurrencyLimitState::new(ConcurrencyLimitConfig {
+            max_concurrent_requests: 2,
+            key_strategy: RateLimitKey::ByIp,
+        });
+
+        let sem = state.semaphore_for("client-a");
+        let _p1 = sem.clone().try_acquire_owned().unwrap();
+        let _p2 = sem.clone().try_acquire_owned().unwrap();
+        assert!(sem.try_acquire_owned().is_err());
+
+        // A different client has its own independent semaphore.
+        let other = state.semaphore_for("client-b");
+        assert!(other.try_acquire_owned().is_ok());
+    }
+
+    #[test]
+    fn test_concurrency_limit_config_default() {
+        let config = ConcurrencyLimitConfig::default();
+        assert_eq!(config.max_concurrent_requests, 10);
     }
 }
@@ -0,0 +1,91 @@
+//! OpenAPI 3 spec generation and interactive docs.
+//!
+//! The spec is generated at compile time from `#[utoipa::path(...)]`
+//! annotations on the handlers in [`crate::handlers`] and `#[derive(ToSchema)]`
+//! on their request/response types, so it can never drift from the routes
+//! `Server::router` actually registers.
+
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::handlers::{
+    BatchItemResult, BatchQueryItemResult, BatchQueryRequest, BatchQueryResponse,
+    BatchUpsertRequest, BatchUpsertResponse, ContextResponse, ErrorDetail, ErrorResponse,
+    SourceInput, StateResponse, TokenRequest, TokenResponse, TranslateRequest, UpsertStateRequest,
+};
+use crate::tools::{InvokeToolRequest, ToolSchema};
+
+#[cfg(feature = "inference")]
+use crate::handlers::{InferEstimate, InferRequest, InferResponse, InferSourceResponse};
+
+/// The generated OpenAPI document for the v1 API.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        crate::handlers::upsert_state,
+        crate::handlers::get_state,
+        crate::handlers::delete_state,
+        crate::handlers::batch_upsert_state,
+        crate::handlers::batch_query_state,
+        crate::handlers::issue_token,
+        crate::handlers::get_context,
+        crate::handlers::translate,
+        crate::handlers::health,
+        crate::handlers::ready,
+        crate::tools::list_tools,
+        crate::tools::invoke_tool,
+    ),
+    components(schemas(
+        UpsertStateRequest,
+        SourceInput,
+        StateResponse,
+        BatchUpsertRequest,
+        BatchUpsertResponse,
+        BatchItemResult,
+        BatchQueryRequest,
+        BatchQueryResponse,
+        BatchQueryItemResult,
+        TokenRequest,
+        TokenResponse,
+        TranslateRequest,
+        ContextResponse,
+        ErrorResponse,
+        ErrorDetail,
+        ToolSchema,
+        InvokeToolRequest,
+    )),
+    tags(
+        (name = "state", description = "User state storage"),
+        (name = "auth", description = "Session token issuance"),
+        (name = "context", description = "Prompt context translation"),
+        (name = "ops", description = "Health and readiness"),
+        (name = "tools", description = "LLM function-calling adapter"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Separately-assembled OpenAPI doc for the `inference` feature, merged into
+/// [`ApiDoc`] at router build time so the `/v1/infer` path and its tagged
+/// `InferSourceResponse` enum only appear in the spec when the feature is on.
+#[cfg(feature = "inference")]
+#[derive(OpenApi)]
+#[openapi(
+    paths(crate::handlers::infer, crate::handlers::infer_stream),
+    components(schemas(InferRequest, InferResponse, InferEstimate, InferSourceResponse)),
+    tags((name = "inference", description = "Message-based axis inference"))
+)]
+pub struct InferenceApiDoc;
+
+/// Build the merged OpenAPI document served at `/openapi.json`.
+pub fn build_spec() -> utoipa::openapi::OpenApi {
+    let mut spec = ApiDoc::openapi();
+    #[cfg(feature = "inference")]
+    spec.merge(InferenceApiDoc::openapi());
+    spec
+}
+
+/// Build the Swagger UI service, mounted at `/docs` and backed by the same
+/// spec served at `/openapi.json`.
+pub fn swagger_ui() -> SwaggerUi {
+    SwaggerUi::new("/docs").url("/openapi.json", build_spec())
+}
@@ -0,0 +1,333 @@
+//! Pluggable rate-limiting backends.
+//!
+//! [`RateLimitBackend`] abstracts over *where* request counts live. The
+//! default [`InMemoryBackend`] keeps a per-client, per-process token bucket,
+//! which is fine for a single replica but doesn't share limits across a
+//! fleet. [`RedisBackend`] shares counts across replicas via Redis, with a
+//! deferred local cache so the common case (well under the limit) never
+//! pays a network round trip.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Outcome of a rate-limit check for one request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    /// Request allowed; `remaining` tokens/requests left in the current window.
+    Allowed {
+        /// Requests remaining in the current window after this one.
+        remaining: u32,
+    },
+    /// Request denied, but the caller should retry after the given duration.
+    RetryAt {
+        /// How long the caller should wait before retrying (at least 1
+        /// second): the time until one token regenerates for the in-memory
+        /// bucket, the full window for the Redis backend.
+        retry_after: Duration,
+    },
+    /// Request denied outright (used by backends that can't compute a
+    /// precise retry time, e.g. a degraded Redis connection failing closed).
+    Denied,
+}
+
+/// A pluggable source of truth for rate-limit counters.
+#[async_trait]
+pub trait RateLimitBackend: Send + Sync {
+    /// Record one request for `key` and decide whether it's allowed under
+    /// `max_requests` per `window`.
+    async fn check(&self, key: &str, max_requests: u32, window: Duration) -> RateLimitDecision;
+
+    /// Evict entries idle longer than `window`, to bound memory for backends
+    /// that don't expire keys on their own. Default is a no-op, for backends
+    /// whose storage expires keys by itself.
+    async fn cleanup(&self, window: Duration) {
+        let _ = window;
+    }
+}
+
+/// A per-client token bucket: `tokens` refills continuously at
+/// `max_requests / window` tokens/second, capped at `max_requests`, rather
+/// than resetting in a lump at a fixed window boundary. This is what keeps a
+/// client from bursting up to `2 * max_requests` in quick succession across
+/// a window rollover, the way a fixed-window counter would allow.
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// The original per-process rate limiter, now behind [`RateLimitBackend`],
+/// implemented as a continuously-refilling token bucket per client key.
+#[derive(Default)]
+pub struct InMemoryBackend {
+    buckets: RwLock<HashMap<String, TokenBucket>>,
+}
+
+impl InMemoryBackend {
+    /// Create an empty in-memory backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop buckets idle longer than `window`, to bound memory.
+    pub async fn cleanup(&self, window: Duration) {
+        let now = Instant::now();
+        let mut buckets = self.buckets.write().await;
+        buckets.retain(|_, bucket| now.duration_since(bucket.last_refill) < window);
+    }
+}
+
+#[async_trait]
+impl RateLimitBackend for InMemoryBackend {
+    async fn check(&self, key: &str, max_requests: u32, window: Duration) -> RateLimitDecision {
+        let now = Instant::now();
+        let max_tokens = max_requests as f64;
+        let refill_rate = max_tokens / window.as_secs_f64().max(f64::MIN_POSITIVE);
+
+        let mut buckets = self.buckets.write().await;
+        let bucket = buckets.entry(key.to_string()).or_insert_with(|| TokenBucket {
+            tokens: max_tokens,
+            last_refill: now,
+        });
+
+        let elapsed_secs = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed_secs * refill_rate).min(max_tokens);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            RateLimitDecision::Allowed {
+                remaining: bucket.tokens as u32,
+            }
+        } else if refill_rate <= 0.0 {
+            // max_requests == 0: the bucket never refills, so there's no
+            // meaningful "next token" time — fall back to the window itself.
+            RateLimitDecision::RetryAt { retry_after: window.max(Duration::from_secs(1)) }
+        } else {
+            let tokens_needed = 1.0 - bucket.tokens;
+            let secs_until_next_token = tokens_needed / refill_rate;
+            RateLimitDecision::RetryAt {
+                retry_after: Duration::from_secs_f64(secs_until_next_token).max(Duration::from_secs(1)),
+            }
+        }
+    }
+
+    async fn cleanup(&self, window: Duration) {
+        InMemoryBackend::cleanup(self, window).await;
+    }
+}
+
+/// Local, lazily-seeded view of a key's count, flushed to Redis periodically
+/// instead of on every request.
+struct LocalCounter {
+    /// Count observed/incremented locally since the last Redis read.
+    count: AtomicU32,
+    /// Increments made locally that have not yet been flushed to Redis.
+    unflushed: AtomicU32,
+    last_flush: std::sync::Mutex<Instant>,
+    /// When this counter was last touched by a request, used by
+    /// [`RedisBackend::cleanup`] to evict entries for windows that have
+    /// rolled over.
+    last_seen: std::sync::Mutex<Instant>,
+}
+
+/// Redis-backed rate limiter with a deferred local counting layer.
+///
+/// The *accepted tradeoff*: the local view may lag the shared Redis count by
+/// up to `flush_interval` (times however many replicas are writing
+/// concurrently), in exchange for not round-tripping to Redis on every
+/// request. A key unseen locally is seeded with a synchronous read; after
+/// that, increments are local-only until a flush is due.
+pub struct RedisBackend {
+    client: redis::Client,
+    local: RwLock<HashMap<String, Arc<LocalCounter>>>,
+    flush_every_n: u32,
+    flush_interval: Duration,
+    flush_seq: AtomicU64,
+}
+
+impl RedisBackend {
+    /// Connect to `redis_url`, flushing local increments to Redis every
+    /// `flush_every_n` increments or `flush_interval`, whichever comes first.
+    pub fn new(redis_url: &str, flush_every_n: u32, flush_interval: Duration) -> redis::RedisResult<Self> {
+        Ok(Self {
+            client: redis::Client::open(redis_url)?,
+            local: RwLock::new(HashMap::new()),
+            flush_every_n: flush_every_n.max(1),
+            flush_interval,
+            flush_seq: AtomicU64::new(0),
+        })
+    }
+
+    fn windowed_key(key: &str, window: Duration) -> String {
+        let window_secs = window.as_secs().max(1);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let bucket = now / window_secs;
+        format!("attuned:ratelimit:{key}:{bucket}")
+    }
+
+    async fn seed_from_redis(&self, redis_key: &str) -> Result<u32, redis::RedisError> {
+        use redis::AsyncCommands;
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let count: Option<u32> = conn.get(redis_key).await?;
+        Ok(count.unwrap_or(0))
+    }
+
+    async fn flush_to_redis(&self, redis_key: &str, window: Duration, delta: u32) -> Result<(), redis::RedisError> {
+        use redis::AsyncCommands;
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let _: () = conn.incr(redis_key, delta).await?;
+        let _: () = conn.expire(redis_key, window.as_secs().max(1) as i64).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RateLimitBackend for RedisBackend {
+    async fn check(&self, key: &str, max_requests: u32, window: Duration) -> RateLimitDecision {
+        let redis_key = Self::windowed_key(key, window);
+
+        let counter = {
+            let local = self.local.read().await;
+            local.get(&redis_key).cloned()
+        };
+
+        let counter = match counter {
+            Some(c) => c,
+            None => {
+                // Unseen locally: do a synchronous read to seed the local view.
+                let seeded = self.seed_from_redis(&redis_key).await.unwrap_or(0);
+                let counter = Arc::new(LocalCounter {
+                    count: AtomicU32::new(seeded),
+                    unflushed: AtomicU32::new(0),
+                    last_flush: std::sync::Mutex::new(Instant::now()),
+                    last_seen: std::sync::Mutex::new(Instant::now()),
+                });
+                self.local.write().await.insert(redis_key.clone(), counter.clone());
+                counter
+            }
+        };
+        *counter.last_seen.lock().unwrap() = Instant::now();
+
+        // If the locally tracked count already exceeds the limit, reject
+        // immediately without touching Redis at all.
+        let count = counter.count.fetch_add(1, Ordering::Relaxed) + 1;
+        counter.unflushed.fetch_add(1, Ordering::Relaxed);
+
+        if count > max_requests {
+            return RateLimitDecision::RetryAt {
+                retry_after: window.max(Duration::from_secs(1)),
+            };
+        }
+
+        let should_flush = counter.unflushed.load(Ordering::Relaxed) >= self.flush_every_n || {
+            let last = *counter.last_flush.lock().unwrap();
+            last.elapsed() >= self.flush_interval
+        };
+
+        if should_flush {
+            // Swap-to-claim: only the caller that actually zeroes a nonzero
+            // delta flushes it, so concurrent callers racing the check above
+            // can't each flush the same increments and inflate the
+            // Redis-side count.
+            let delta = counter.unflushed.swap(0, Ordering::Relaxed);
+            if delta > 0 {
+                *counter.last_flush.lock().unwrap() = Instant::now();
+                let this_seq = self.flush_seq.fetch_add(1, Ordering::Relaxed);
+                tracing::trace!(seq = this_seq, key = %redis_key, delta, "flushing rate limit counter to redis");
+                if let Err(e) = self.flush_to_redis(&redis_key, window, delta).await {
+                    tracing::warn!(error = %e, "failed to flush rate limit counter to redis; local count may drift");
+                }
+            }
+        }
+
+        RateLimitDecision::Allowed {
+            remaining: max_requests.saturating_sub(count),
+        }
+    }
+
+    /// Evict local counters for windows that have rolled over. `windowed_key`
+    /// bakes the current time bucket into the map key, so without this a new
+    /// entry accumulates every `window` forever; a counter untouched for a
+    /// full window belongs to a bucket no future request will ever look up
+    /// again.
+    async fn cleanup(&self, window: Duration) {
+        let now = Instant::now();
+        let mut local = self.local.write().await;
+        local.retain(|_, counter| now.duration_since(*counter.last_seen.lock().unwrap()) < window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_token_bucket_allows_burst_up_to_max() {
+        let backend = InMemoryBackend::new();
+        let window = Duration::from_secs(60);
+
+        for _ in 0..3 {
+            assert!(matches!(
+                backend.check("client", 3, window).await,
+                RateLimitDecision::Allowed { .. }
+            ));
+        }
+        assert!(matches!(backend.check("client", 3, window).await, RateLimitDecision::RetryAt { .. }));
+    }
+
+    /// Distinguishes a token bucket from a fixed window: a partial refill
+    /// well before the window fully elapses should still grant a request,
+    /// whereas a fixed window would keep denying until the whole window
+    /// rolls over.
+    #[tokio::test]
+    async fn test_token_bucket_refills_continuously_before_window_elapses() {
+        let backend = InMemoryBackend::new();
+        let max_requests = 2;
+        let window = Duration::from_millis(100);
+
+        assert!(matches!(
+            backend.check("client", max_requests, window).await,
+            RateLimitDecision::Allowed { .. }
+        ));
+        assert!(matches!(
+            backend.check("client", max_requests, window).await,
+            RateLimitDecision::Allowed { .. }
+        ));
+        assert!(matches!(
+            backend.check("client", max_requests, window).await,
+            RateLimitDecision::RetryAt { .. }
+        ));
+
+        // Well under the 100ms window, but long enough for the continuous
+        // refill (20 tokens/sec here) to produce at least one more token.
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        assert!(matches!(
+            backend.check("client", max_requests, window).await,
+            RateLimitDecision::Allowed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_different_keys_are_independent() {
+        let backend = InMemoryBackend::new();
+        let window = Duration::from_secs(60);
+
+        assert!(matches!(
+            backend.check("client-a", 1, window).await,
+            RateLimitDecision::Allowed { .. }
+        ));
+        assert!(matches!(backend.check("client-a", 1, window).await, RateLimitDecision::RetryAt { .. }));
+        assert!(matches!(
+            backend.check("client-b", 1, window).await,
+            RateLimitDecision::Allowed { .. }
+        ));
+    }
+}
@@ -1,46 +1,74 @@
 //! HTTP server implementation.
 
+use crate::backend::{self, BackendError, BoxedStore};
 use crate::config::ServerConfig;
 use crate::error::HttpError;
 use crate::handlers::{
-    delete_state, get_context, get_state, health, ready, translate, upsert_state, AppState,
+    batch_query_state, batch_upsert_state, delete_state, get_context, get_state, health,
+    issue_token, ready, translate, upsert_state, AppState,
 };
-use crate::middleware::security_headers;
+use crate::middleware::{
+    api_key_auth, concurrency_limit, rate_limit, security_headers, AuthState,
+    ConcurrencyLimitState, RateLimitState,
+};
+use crate::openapi;
+use crate::tools::{invoke_tool, list_tools};
 use attuned_core::HealthCheck;
 use attuned_store::StateStore;
 use axum::{
+    error_handling::HandleErrorLayer,
+    extract::DefaultBodyLimit,
+    http::StatusCode,
     middleware,
     routing::{delete, get, post},
-    Router,
+    BoxError, Router,
 };
+use std::future::Future;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use tower::ServiceBuilder;
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
 
 #[cfg(feature = "inference")]
-use crate::handlers::infer;
+use crate::handlers::{infer, infer_stream};
 
 /// HTTP server for the Attuned API.
 pub struct Server<S: StateStore + HealthCheck> {
     state: Arc<AppState<S>>,
     config: ServerConfig,
+    rate_limit: RateLimitState,
+    concurrency_limit: ConcurrencyLimitState,
+    auth: AuthState,
 }
 
 impl<S: StateStore + HealthCheck + 'static> Server<S> {
     /// Create a new server with the given store and configuration.
     pub fn new(store: S, config: ServerConfig) -> Self {
         #[cfg(feature = "inference")]
-        let state = if config.enable_inference {
-            Arc::new(AppState::with_inference(
-                store,
-                config.inference_config.clone(),
-            ))
+        let mut app_state = if config.enable_inference {
+            AppState::with_inference(store, config.inference_config.clone())
         } else {
-            Arc::new(AppState::new(store))
+            AppState::new(store)
         };
         #[cfg(not(feature = "inference"))]
-        let state = Arc::new(AppState::new(store));
+        let mut app_state = AppState::new(store);
+
+        app_state.jwt = config.jwt.clone();
+        let state = Arc::new(app_state);
 
-        Self { state, config }
+        let rate_limit = RateLimitState::new(config.rate_limit.clone());
+        let concurrency_limit = ConcurrencyLimitState::new(config.concurrency_limit.clone());
+        let auth = AuthState::new(config.auth.clone());
+
+        Self {
+            state,
+            config,
+            rate_limit,
+            concurrency_limit,
+            auth,
+        }
     }
 
     /// Build the router with all routes.
@@ -51,33 +79,116 @@ impl<S: StateStore + HealthCheck + 'static> Server<S> {
             .route("/v1/state", post(upsert_state::<S>))
             .route("/v1/state/{user_id}", get(get_state::<S>))
             .route("/v1/state/{user_id}", delete(delete_state::<S>))
+            .route("/v1/state/batch", post(batch_upsert_state::<S>))
+            .route("/v1/state/query", post(batch_query_state::<S>))
+            // Auth
+            .route("/v1/auth/token", post(issue_token::<S>))
             // Context/translation
             .route("/v1/context/{user_id}", get(get_context::<S>))
             .route("/v1/translate", post(translate::<S>))
+            // LLM function-calling adapter
+            .route("/v1/tools", get(list_tools))
+            .route("/v1/tools/invoke", post(invoke_tool::<S>))
             // Operations
             .route("/health", get(health::<S>))
             .route("/ready", get(ready::<S>));
 
-        // Add inference endpoint if feature enabled
-        #[cfg(feature = "inference")]
-        let typed_router = typed_router.route("/v1/infer", post(infer::<S>));
+        // Bound how large a single request body may be; layered here (before
+        // the inference routes are merged in below) so it doesn't also clamp
+        // the inference routes' own, separately-configured ceiling. Axum's
+        // own built-in 2MB default limit is disabled, since it would
+        // otherwise pre-empt this layer for any body between 2MB and
+        // `body_limit`/the inference ceiling below.
+        let typed_router = typed_router
+            .layer(RequestBodyLimitLayer::new(self.config.body_limit))
+            .layer(DefaultBodyLimit::disable());
 
         // Apply state and convert to Router<()>
         let mut router = typed_router.with_state(self.state.clone());
 
+        // Inference requests can carry a longer message than a state upsert,
+        // so they get their own (typically larger) body size ceiling rather
+        // than sharing the default routes' limit.
+        #[cfg(feature = "inference")]
+        {
+            let inference_router = Router::new()
+                .route("/v1/infer", post(infer::<S>))
+                .route("/v1/infer/stream", post(infer_stream::<S>))
+                .layer(RequestBodyLimitLayer::new(
+                    self.config.effective_inference_body_limit(),
+                ))
+                .layer(DefaultBodyLimit::disable())
+                .with_state(self.state.clone());
+            router = router.merge(inference_router);
+        }
+
+        // Mount the generated OpenAPI spec (served at /openapi.json) and the
+        // Swagger UI docs page (served at /docs).
+        router = router.merge(openapi::swagger_ui());
+
         // Add security headers middleware (outermost layer, runs last on request, first on response)
         if self.config.security_headers {
             router = router.layer(middleware::from_fn(security_headers));
         }
 
+        // Enforce the configured per-client request rate (keyed by IP by
+        // default; requires ConnectInfo, so the server must be served via
+        // `into_make_service_with_connect_info`).
+        router = router.layer(middleware::from_fn_with_state(
+            self.rate_limit.clone(),
+            rate_limit,
+        ));
+
+        // Bound concurrent in-flight requests per client, independent of the
+        // request-rate limit above; also keyed by IP by default and requires
+        // ConnectInfo.
+        router = router.layer(middleware::from_fn_with_state(
+            self.concurrency_limit.clone(),
+            concurrency_limit,
+        ));
+
+        // Validate the caller's API key (or resolve an anonymous identity if
+        // none are configured) and insert the resulting `Identity` into
+        // request extensions — consumed by the rate limiter above and
+        // available to handlers. Must run earlier in the stack than
+        // `rate_limit`, so its layer is added after rate_limit's.
+        router = router.layer(middleware::from_fn_with_state(
+            self.auth.clone(),
+            api_key_auth,
+        ));
+
+        // Cross-origin access is opt-in; `to_layer` returns `None` under the
+        // default same-origin-only config.
+        if let Some(cors) = self.config.cors.to_layer() {
+            router = router.layer(cors);
+        }
+
         // Add tracing
         router = router.layer(TraceLayer::new_for_http());
 
+        // Bound how long a single request may take; a stuck store or
+        // inference call gets 408'd instead of pinning the connection.
+        router = router.layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(handle_timeout_error))
+                .layer(TimeoutLayer::new(self.config.request_timeout)),
+        );
+
         router
     }
 
-    /// Run the server until shutdown.
+    /// Run the server until Ctrl+C or SIGTERM is received, draining
+    /// in-flight requests before the listener closes.
     pub async fn run(self) -> Result<(), HttpError> {
+        self.run_with_shutdown(shutdown_signal()).await
+    }
+
+    /// Run the server until `shutdown` resolves, draining in-flight requests
+    /// before the listener closes (`axum::serve::Serve::with_graceful_shutdown`).
+    pub async fn run_with_shutdown(
+        self,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<(), HttpError> {
         let app = self.router();
 
         tracing::info!(
@@ -85,6 +196,7 @@ impl<S: StateStore + HealthCheck + 'static> Server<S> {
             security_headers = %self.config.security_headers,
             auth_enabled = %self.config.auth.is_enabled(),
             rate_limit = %self.config.rate_limit.max_requests,
+            request_timeout_secs = %self.config.request_timeout.as_secs(),
             "starting HTTP server"
         );
 
@@ -95,19 +207,89 @@ impl<S: StateStore + HealthCheck + 'static> Server<S> {
                 message: e.to_string(),
             })?;
 
-        axum::serve(listener, app)
-            .await
-            .map_err(|e| HttpError::Request(e.to_string()))?;
+        // Sweep idle rate-limit entries for as long as the server runs, so a
+        // churning client population doesn't grow the backend's table
+        // unbounded; cancelled once the listener starts shutting down.
+        let sweeper = {
+            let rate_limit = self.rate_limit.clone();
+            tokio::spawn(async move { rate_limit.run_sweeper().await })
+        };
+
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown)
+        .await
+        .map_err(|e| HttpError::Request(e.to_string()))?;
+
+        sweeper.abort();
+        tracing::info!("HTTP server shut down cleanly");
 
         Ok(())
     }
 }
 
+impl Server<BoxedStore> {
+    /// Create a server whose store is selected at runtime from `uri`'s
+    /// scheme (`memory://`, `file:///path`, `postgres://...`), rather than
+    /// fixed at compile time via [`Server::new`]'s generic parameter. Lets
+    /// ops repoint a single binary at a different persistence layer purely
+    /// from configuration.
+    pub async fn from_uri(uri: &str, config: ServerConfig) -> Result<Self, BackendError> {
+        let store = backend::connect(uri).await?;
+        Ok(Self::new(store, config))
+    }
+}
+
+/// Resolves on Ctrl+C or (on Unix) SIGTERM, whichever comes first.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("received Ctrl+C, shutting down"),
+        _ = terminate => tracing::info!("received SIGTERM, shutting down"),
+    }
+}
+
+/// Maps a timeout (or other) error from the [`TimeoutLayer`] stack into a
+/// response: `408` when the request simply ran too long, `503` for anything
+/// else the layer stack might surface.
+async fn handle_timeout_error(err: BoxError) -> (StatusCode, String) {
+    if err.is::<tower_http::timeout::error::Elapsed>() {
+        (
+            StatusCode::REQUEST_TIMEOUT,
+            "request exceeded the configured timeout".to_string(),
+        )
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("unhandled internal error: {err}"),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use attuned_store::MemoryStore;
     use axum::body::Body;
+    use axum::extract::ConnectInfo;
     use axum::http::{Request, StatusCode};
     use tower::ServiceExt;
 
@@ -117,6 +299,13 @@ mod tests {
         Server::new(store, config)
     }
 
+    /// The rate-limit middleware requires `ConnectInfo`, which is normally
+    /// inserted by `into_make_service_with_connect_info`; tests drive the
+    /// router directly via `oneshot`, so they attach it as an extension.
+    fn test_addr() -> SocketAddr {
+        "127.0.0.1:12345".parse().unwrap()
+    }
+
     #[tokio::test]
     async fn test_health_endpoint() {
         let server = test_server();
@@ -126,6 +315,7 @@ mod tests {
             .oneshot(
                 Request::builder()
                     .uri("/health")
+                    .extension(ConnectInfo(test_addr()))
                     .body(Body::empty())
                     .unwrap(),
             )
@@ -144,6 +334,7 @@ mod tests {
             .oneshot(
                 Request::builder()
                     .uri("/ready")
+                    .extension(ConnectInfo(test_addr()))
                     .body(Body::empty())
                     .unwrap(),
             )
@@ -162,6 +353,7 @@ mod tests {
             .oneshot(
                 Request::builder()
                     .uri("/v1/state/nonexistent")
+                    .extension(ConnectInfo(test_addr()))
                     .body(Body::empty())
                     .unwrap(),
             )
@@ -185,6 +377,7 @@ mod tests {
                     .method("POST")
                     .uri("/v1/state")
                     .header("content-type", "application/json")
+                    .extension(ConnectInfo(test_addr()))
                     .body(Body::from(body))
                     .unwrap(),
             )
@@ -198,6 +391,7 @@ mod tests {
             .oneshot(
                 Request::builder()
                     .uri("/v1/state/test_user")
+                    .extension(ConnectInfo(test_addr()))
                     .body(Body::empty())
                     .unwrap(),
             )
@@ -216,6 +410,7 @@ mod tests {
             .oneshot(
                 Request::builder()
                     .uri("/health")
+                    .extension(ConnectInfo(test_addr()))
                     .body(Body::empty())
                     .unwrap(),
             )
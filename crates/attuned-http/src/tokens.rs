@@ -0,0 +1,299 @@
+//! Session/refresh token issuance and validation.
+//!
+//! Alongside the static API keys in [`crate::middleware::AuthConfig`],
+//! callers can exchange a valid credential for a short-lived session
+//! token via `POST /v1/auth/token` — useful for browser clients that
+//! shouldn't hold the master API key long-term. Tokens encode their kind
+//! as a single-character prefix (`'s'` session, `'r'` refresh) so
+//! validation can route on it without a lookup.
+
+use crate::middleware::{AuthConfig, AuthenticationError, Authenticator, Identity};
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Prefix identifying a short-lived session token.
+pub const SESSION_PREFIX: char = 's';
+/// Prefix identifying a long-lived refresh token.
+pub const REFRESH_PREFIX: char = 'r';
+
+/// The kind of token encoded in its first character.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Short-lived; accepted directly by [`TokenAuthenticator`].
+    Session,
+    /// Long-lived; only ever exchanged for a session token, never accepted
+    /// as a request credential itself.
+    Refresh,
+}
+
+impl TokenKind {
+    fn prefix(self) -> char {
+        match self {
+            TokenKind::Session => SESSION_PREFIX,
+            TokenKind::Refresh => REFRESH_PREFIX,
+        }
+    }
+}
+
+/// Errors produced while looking up a token.
+#[derive(Debug, Clone, thiserror::Error)]
+#[non_exhaustive]
+pub enum TokenError {
+    /// The token didn't carry a recognized kind prefix.
+    #[error("malformed token")]
+    Malformed,
+    /// The token isn't one this server issued (or it was revoked).
+    #[error("unknown token")]
+    Unknown,
+    /// The token was valid but its TTL has elapsed.
+    #[error("token has expired")]
+    Expired,
+    /// The token is a known kind, but not the kind expected for this
+    /// operation (e.g. a session token presented where a refresh token
+    /// was required).
+    #[error("wrong token type for this operation")]
+    WrongKind,
+}
+
+#[derive(Clone, Debug)]
+struct TokenRecord {
+    owner_id: String,
+    kind: TokenKind,
+    /// `None` for refresh tokens, which don't expire on their own.
+    expires_at_unix_ms: Option<i64>,
+}
+
+/// Configuration for the token subsystem.
+#[derive(Clone, Debug)]
+pub struct TokenConfig {
+    /// How long a freshly issued session token remains valid.
+    pub session_ttl: Duration,
+}
+
+impl Default for TokenConfig {
+    fn default() -> Self {
+        Self {
+            session_ttl: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+/// Issues and validates session/refresh tokens.
+///
+/// Tokens are looked up by their full string, so validation is O(1)
+/// regardless of kind; the kind prefix is only used to reject a token
+/// presented for the wrong operation without touching the map.
+#[derive(Clone)]
+pub struct TokenStore {
+    config: TokenConfig,
+    tokens: Arc<DashMap<String, TokenRecord>>,
+}
+
+impl TokenStore {
+    /// Create an empty token store.
+    pub fn new(config: TokenConfig) -> Self {
+        Self {
+            config,
+            tokens: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Issue a long-lived refresh token for `owner_id`. Refresh tokens
+    /// never expire on their own; revoke one by dropping it from the
+    /// store (not currently exposed — out of scope for this subsystem).
+    pub fn issue_refresh_token(&self, owner_id: impl Into<String>) -> String {
+        let token = Self::generate(TokenKind::Refresh);
+        self.tokens.insert(
+            token.clone(),
+            TokenRecord {
+                owner_id: owner_id.into(),
+                kind: TokenKind::Refresh,
+                expires_at_unix_ms: None,
+            },
+        );
+        token
+    }
+
+    /// Issue a short-lived session token for `owner_id`, returning the
+    /// token and its expiry (Unix ms).
+    pub fn issue_session_token(&self, owner_id: impl Into<String>) -> (String, i64) {
+        let token = Self::generate(TokenKind::Session);
+        let expires_at_unix_ms = now_unix_ms() + self.config.session_ttl.as_millis() as i64;
+        self.tokens.insert(
+            token.clone(),
+            TokenRecord {
+                owner_id: owner_id.into(),
+                kind: TokenKind::Session,
+                expires_at_unix_ms: Some(expires_at_unix_ms),
+            },
+        );
+        (token, expires_at_unix_ms)
+    }
+
+    /// Resolve the owner of a still-valid refresh token, without issuing
+    /// anything.
+    pub fn owner_of_refresh_token(&self, token: &str) -> Result<String, TokenError> {
+        self.lookup(token, TokenKind::Refresh).map(|r| r.owner_id)
+    }
+
+    /// Validate a session token, returning its owner if it's known and
+    /// unexpired.
+    pub fn owner_of_session_token(&self, token: &str) -> Result<String, TokenError> {
+        self.lookup(token, TokenKind::Session).map(|r| r.owner_id)
+    }
+
+    fn lookup(&self, token: &str, expected_kind: TokenKind) -> Result<TokenRecord, TokenError> {
+        let kind = match token.chars().next() {
+            Some(c) if c == SESSION_PREFIX => TokenKind::Session,
+            Some(c) if c == REFRESH_PREFIX => TokenKind::Refresh,
+            _ => return Err(TokenError::Malformed),
+        };
+        if kind != expected_kind {
+            return Err(TokenError::WrongKind);
+        }
+
+        let record = self.tokens.get(token).ok_or(TokenError::Unknown)?;
+        if let Some(expires_at) = record.expires_at_unix_ms {
+            if now_unix_ms() >= expires_at {
+                return Err(TokenError::Expired);
+            }
+        }
+        Ok(record.clone())
+    }
+
+    fn generate(kind: TokenKind) -> String {
+        format!("{}{}", kind.prefix(), uuid::Uuid::new_v4().simple())
+    }
+}
+
+fn now_unix_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// [`Authenticator`] that accepts either a configured static API key or a
+/// non-expired session token, so frontends can hold a rotating short-lived
+/// credential instead of shipping the master key.
+pub struct TokenAuthenticator {
+    config: AuthConfig,
+    tokens: TokenStore,
+}
+
+impl TokenAuthenticator {
+    /// Build an authenticator from the static-key config and token store it
+    /// should accept credentials against.
+    pub fn new(config: AuthConfig, tokens: TokenStore) -> Self {
+        Self { config, tokens }
+    }
+}
+
+#[async_trait]
+impl Authenticator for TokenAuthenticator {
+    async fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        path: &str,
+    ) -> Result<Identity, AuthenticationError> {
+        if !self.config.requires_auth(path) || !self.config.is_enabled() {
+            return Ok(Identity {
+                user_id: "anonymous".to_string(),
+                tier: "anonymous".to_string(),
+                scopes: vec![],
+            });
+        }
+
+        let auth_header = headers
+            .get(&self.config.header_name)
+            .and_then(|v| v.to_str().ok());
+
+        let credential = match auth_header {
+            Some(value) if value.starts_with(&self.config.prefix) => {
+                &value[self.config.prefix.len()..]
+            }
+            Some(_) => return Err(AuthenticationError::InvalidCredentials),
+            None => return Err(AuthenticationError::MissingCredentials),
+        };
+
+        if credential.starts_with(SESSION_PREFIX) {
+            return match self.tokens.owner_of_session_token(credential) {
+                Ok(owner_id) => Ok(Identity {
+                    user_id: owner_id,
+                    tier: "default".to_string(),
+                    scopes: vec![],
+                }),
+                Err(TokenError::Expired) => Err(AuthenticationError::Expired),
+                Err(_) => Err(AuthenticationError::InvalidCredentials),
+            };
+        }
+
+        if self.config.validate_key(credential) {
+            return Ok(Identity {
+                user_id: credential.to_string(),
+                tier: "default".to_string(),
+                scopes: vec![],
+            });
+        }
+
+        tracing::warn!(path = %path, "invalid credential attempt");
+        Err(AuthenticationError::InvalidCredentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_token_round_trips() {
+        let store = TokenStore::new(TokenConfig::default());
+        let (token, _expiry) = store.issue_session_token("user_1");
+        assert!(token.starts_with(SESSION_PREFIX));
+        assert_eq!(store.owner_of_session_token(&token).unwrap(), "user_1");
+    }
+
+    #[test]
+    fn test_refresh_token_rejected_as_session_token() {
+        let store = TokenStore::new(TokenConfig::default());
+        let refresh = store.issue_refresh_token("user_1");
+        assert!(matches!(
+            store.owner_of_session_token(&refresh),
+            Err(TokenError::WrongKind)
+        ));
+    }
+
+    #[test]
+    fn test_expired_session_token_is_rejected() {
+        let store = TokenStore::new(TokenConfig {
+            session_ttl: Duration::from_millis(0),
+        });
+        let (token, _expiry) = store.issue_session_token("user_1");
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(matches!(
+            store.owner_of_session_token(&token),
+            Err(TokenError::Expired)
+        ));
+    }
+
+    #[test]
+    fn test_unknown_token_is_rejected() {
+        let store = TokenStore::new(TokenConfig::default());
+        assert!(matches!(
+            store.owner_of_session_token("snonexistent"),
+            Err(TokenError::Unknown)
+        ));
+    }
+
+    #[test]
+    fn test_malformed_token_is_rejected() {
+        let store = TokenStore::new(TokenConfig::default());
+        assert!(matches!(
+            store.owner_of_session_token("not-a-token"),
+            Err(TokenError::Malformed)
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+//! LLM tool/function-calling adapter.
+//!
+//! Exposes a subset of the server's capabilities as callable tools in the
+//! `{ "name", "description", "parameters": <JSON Schema> }` shape used by
+//! OpenAI- and Anthropic-style `tools` arrays, so an LLM agent can fetch and
+//! update a user's behavioral state mid-conversation instead of only
+//! fetching [`attuned_core::PromptContext`] out-of-band.
+
+use attuned_store::StateStore;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::handlers::{authorize_path_user, AppState, ContextResponse, ErrorResponse, StateResponse};
+
+/// A callable tool description in the widely-used function-calling format.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ToolSchema {
+    /// Tool name, used as the `name` field when invoking.
+    pub name: String,
+    /// Human-readable description shown to the model.
+    pub description: String,
+    /// JSON Schema object describing the tool's arguments.
+    pub parameters: serde_json::Value,
+}
+
+/// Build the list of tools this server exposes.
+///
+/// The `infer_axes` tool is only advertised when the `inference` feature is
+/// enabled, since there is no inference engine to dispatch to otherwise.
+pub fn tool_schemas() -> Vec<ToolSchema> {
+    let mut tools = vec![
+        ToolSchema {
+            name: "get_user_context".to_string(),
+            description: "Get the translated PromptContext (guidelines, tone, verbosity, flags) \
+                          for a user's current behavioral state."
+                .to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "user_id": { "type": "string", "description": "The user ID to fetch context for." }
+                },
+                "required": ["user_id"]
+            }),
+        },
+        ToolSchema {
+            name: "get_user_state".to_string(),
+            description: "Get the raw latest axis values stored for a user.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "user_id": { "type": "string", "description": "The user ID to fetch state for." }
+                },
+                "required": ["user_id"]
+            }),
+        },
+    ];
+
+    #[cfg(feature = "inference")]
+    tools.push(ToolSchema {
+        name: "infer_axes".to_string(),
+        description: "Infer behavioral axis values from a message of text, optionally updating \
+                      a user's running baseline."
+            .to_string(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "message": { "type": "string", "description": "The message text to analyze." },
+                "user_id": { "type": "string", "description": "Optional user ID for baseline comparison." }
+            },
+            "required": ["message"]
+        }),
+    });
+
+    tools
+}
+
+/// GET /v1/tools - List the tool schemas available for function-calling.
+#[utoipa::path(
+    get,
+    path = "/v1/tools",
+    tag = "tools",
+    responses((status = 200, description = "Available tools", body = [ToolSchema]))
+)]
+pub async fn list_tools() -> impl IntoResponse {
+    Json(tool_schemas())
+}
+
+/// Request body for invoking a tool.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct InvokeToolRequest {
+    /// Name of the tool to invoke, matching a [`ToolSchema::name`].
+    pub name: String,
+    /// Arguments to pass to the tool; only the presence of its required
+    /// string fields is checked, not the full JSON Schema.
+    pub arguments: serde_json::Value,
+}
+
+/// Errors that can occur while dispatching a tool call.
+///
+/// An unknown tool or bad arguments map to 400, so a calling model can
+/// recover (e.g. retry with corrected arguments) instead of the turn
+/// failing outright; an unavailable tool or store maps to 503, and auth
+/// failures keep their own 401/403.
+#[derive(Debug, Error)]
+pub enum ToolError {
+    /// No tool with this name is registered.
+    #[error("unknown tool: {0}")]
+    UnknownTool(String),
+
+    /// The `arguments` value didn't match the tool's expected shape.
+    #[error("invalid arguments: {0}")]
+    InvalidArguments(String),
+
+    /// The tool exists but isn't usable in this server's configuration
+    /// (e.g. `infer_axes` without the `inference` feature).
+    #[error("tool unavailable: {0}")]
+    Unavailable(String),
+
+    /// The caller isn't authorized to act on the requested `user_id` (see
+    /// [`authorize_path_user`]).
+    #[error(transparent)]
+    Forbidden(#[from] crate::jwt_auth::AuthError),
+}
+
+impl IntoResponse for ToolError {
+    fn into_response(self) -> axum::response::Response {
+        // `AuthError` already knows its own status (401 vs 403) and JSON body.
+        if let ToolError::Forbidden(e) = self {
+            return e.into_response();
+        }
+
+        let (status, code) = match &self {
+            ToolError::UnknownTool(_) => (StatusCode::BAD_REQUEST, "UNKNOWN_TOOL"),
+            ToolError::InvalidArguments(_) => (StatusCode::BAD_REQUEST, "INVALID_ARGUMENTS"),
+            ToolError::Unavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "TOOL_UNAVAILABLE"),
+            ToolError::Forbidden(_) => unreachable!("handled above"),
+        };
+        (status, Json(ErrorResponse::new(code, &self.to_string()))).into_response()
+    }
+}
+
+fn require_str_arg(arguments: &serde_json::Value, field: &str) -> Result<String, ToolError> {
+    arguments
+        .get(field)
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| ToolError::InvalidArguments(format!("missing required field `{field}`")))
+}
+
+/// POST /v1/tools/invoke - Dispatch a named tool call and return its result.
+#[utoipa::path(
+    post,
+    path = "/v1/tools/invoke",
+    tag = "tools",
+    request_body = InvokeToolRequest,
+    responses(
+        (status = 200, description = "Tool result", body = Object),
+        (status = 400, description = "UNKNOWN_TOOL or INVALID_ARGUMENTS", body = ErrorResponse),
+    )
+)]
+pub async fn invoke_tool<S: StateStore + 'static>(
+    State(state): State<Arc<AppState<S>>>,
+    headers: HeaderMap,
+    Json(body): Json<InvokeToolRequest>,
+) -> Result<Json<serde_json::Value>, ToolError> {
+    match body.name.as_str() {
+        "get_user_context" => {
+            let user_id = require_str_arg(&body.arguments, "user_id")?;
+            authorize_path_user(&state, &headers, &user_id)?;
+            let snapshot = state
+                .store
+                .get_latest(&user_id)
+                .await
+                .map_err(|e| ToolError::Unavailable(e.to_string()))?
+                .ok_or_else(|| ToolError::InvalidArguments(format!("no state for user {user_id}")))?;
+            let context = ContextResponse::from(state.translator.to_prompt_context(&snapshot));
+            Ok(Json(serde_json::to_value(context).unwrap()))
+        }
+        "get_user_state" => {
+            let user_id = require_str_arg(&body.arguments, "user_id")?;
+            authorize_path_user(&state, &headers, &user_id)?;
+            let snapshot = state
+                .store
+                .get_latest(&user_id)
+                .await
+                .map_err(|e| ToolError::Unavailable(e.to_string()))?
+                .ok_or_else(|| ToolError::InvalidArguments(format!("no state for user {user_id}")))?;
+            let response = StateResponse::from(snapshot);
+            Ok(Json(serde_json::to_value(response).unwrap()))
+        }
+        #[cfg(feature = "inference")]
+        "infer_axes" => {
+            let message = require_str_arg(&body.arguments, "message")?;
+            let engine = state
+                .inference_engine
+                .as_ref()
+                .ok_or_else(|| ToolError::Unavailable("inference is not enabled".to_string()))?;
+
+            let inferred = match body.arguments.get("user_id").and_then(|v| v.as_str()) {
+                Some(user_id) => {
+                    let mut baseline_ref = state
+                        .baselines
+                        .entry(user_id.to_string())
+                        .or_insert_with(|| engine.new_baseline());
+                    engine.infer_with_baseline(&message, &mut baseline_ref, None)
+                }
+                None => engine.infer(&message),
+            };
+
+            let estimates: Vec<_> = inferred
+                .all()
+                .map(|est| serde_json::json!({ "axis": est.axis, "value": est.value, "confidence": est.confidence }))
+                .collect();
+            Ok(Json(serde_json::json!({ "estimates": estimates })))
+        }
+        other => Err(ToolError::UnknownTool(other.to_string())),
+    }
+}
@@ -0,0 +1,33 @@
+//! sea-orm entity for versioned state snapshot rows.
+//!
+//! Rows are append-only: each `upsert_latest` inserts a new row rather than
+//! overwriting in place, so the table doubles as the history log.
+
+use sea_orm::entity::prelude::*;
+
+/// A single stored version of a user's state.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "state_snapshots")]
+pub struct Model {
+    /// Auto-incrementing row id (insertion order, used to break timestamp ties).
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// User this row belongs to.
+    #[sea_orm(indexed)]
+    pub user_id: String,
+    /// Snapshot source, serialized the same way as the HTTP wire format
+    /// (`self_report` / `inferred` / `mixed`).
+    pub source: String,
+    /// Confidence level of the snapshot.
+    pub confidence: f32,
+    /// Axis values, serialized as JSON (`BTreeMap<String, f32>`).
+    pub axes_json: String,
+    /// Timestamp of this version (Unix ms).
+    pub created_at_unix_ms: i64,
+}
+
+/// No relations; this is a single append-only table.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
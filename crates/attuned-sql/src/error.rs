@@ -0,0 +1,12 @@
+//! SQL-specific error types.
+
+use thiserror::Error;
+
+/// Errors specific to the SQL backend.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SqlError {
+    /// A database operation (connect, query, migrate) failed.
+    #[error("database error: {0}")]
+    Database(#[from] sea_orm::DbErr),
+}
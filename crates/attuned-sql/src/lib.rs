@@ -0,0 +1,37 @@
+//! # attuned-sql
+//!
+//! Durable SQL storage backend for Attuned, built on `sea-orm`.
+//!
+//! Unlike [`attuned_store::MemoryStore`], which loses all state on restart,
+//! [`SqlStore`] persists every `upsert_latest` as a new row, giving
+//! `get_history` real historical snapshots and surviving process restarts.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use attuned_sql::{SqlStore, SqlStoreConfig};
+//! use attuned_store::StateStore;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let config = SqlStoreConfig {
+//!         database_url: "sqlite://attuned.db".to_string(),
+//!         ..Default::default()
+//!     };
+//!
+//!     let store = SqlStore::new(config).await?;
+//!     // Use store via StateStore trait...
+//!     Ok(())
+//! }
+//! ```
+
+#![deny(missing_docs)]
+
+mod config;
+mod entity;
+mod error;
+mod store;
+
+pub use config::SqlStoreConfig;
+pub use error::SqlError;
+pub use store::SqlStore;
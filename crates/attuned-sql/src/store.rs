@@ -0,0 +1,257 @@
+//! sea-orm-backed `StateStore` implementation.
+
+use crate::config::SqlStoreConfig;
+use crate::entity::{self, Entity as StateSnapshotEntity};
+use crate::error::SqlError;
+use async_trait::async_trait;
+use attuned_core::{ComponentHealth, HealthCheck, Source, StateSnapshot};
+use attuned_store::{StateStore, StoreError};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, Database, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, Statement,
+};
+
+/// Durable [`StateStore`] backed by a SQL database via `sea-orm`.
+///
+/// Each `upsert_latest` call inserts a *new* row rather than overwriting in
+/// place, so `get_history` returns real prior versions and `get_latest` is
+/// simply "the newest row for this user". `delete` removes every row for
+/// the user, satisfying GDPR-style erasure requests.
+pub struct SqlStore {
+    db: DatabaseConnection,
+}
+
+impl SqlStore {
+    /// Connect to `config.database_url` and ensure the backing table exists.
+    pub async fn new(config: SqlStoreConfig) -> Result<Self, SqlError> {
+        let db = Database::connect(&config.database_url).await?;
+        Self::migrate(&db).await?;
+        Ok(Self { db })
+    }
+
+    async fn migrate(db: &DatabaseConnection) -> Result<(), SqlError> {
+        let backend = db.get_database_backend();
+        // Postgres has no AUTOINCREMENT keyword; it uses a serial column.
+        let id_column = match backend {
+            sea_orm::DatabaseBackend::Postgres => "id BIGSERIAL PRIMARY KEY",
+            _ => "id INTEGER PRIMARY KEY AUTOINCREMENT",
+        };
+        db.execute(Statement::from_string(
+            backend,
+            format!(
+                r#"
+            CREATE TABLE IF NOT EXISTS state_snapshots (
+                {id_column},
+                user_id TEXT NOT NULL,
+                source TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                axes_json TEXT NOT NULL,
+                created_at_unix_ms BIGINT NOT NULL
+            )
+            "#
+            ),
+        ))
+        .await?;
+
+        db.execute(Statement::from_string(
+            backend,
+            "CREATE INDEX IF NOT EXISTS idx_state_snapshots_user_id ON state_snapshots (user_id)"
+                .to_string(),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn parse_source(raw: &str) -> Result<Source, StoreError> {
+    match raw {
+        "self_report" => Ok(Source::SelfReport),
+        "inferred" => Ok(Source::Inferred),
+        "mixed" => Ok(Source::Mixed),
+        other => Err(StoreError::internal(format!("unknown stored source '{other}'"))),
+    }
+}
+
+fn row_to_snapshot(model: entity::Model) -> Result<StateSnapshot, StoreError> {
+    let source = parse_source(&model.source)?;
+    let axes = serde_json::from_str(&model.axes_json)
+        .map_err(|e| StoreError::internal_with_source("failed to deserialize stored axes", e))?;
+
+    let mut snapshot = StateSnapshot::builder()
+        .user_id(&model.user_id)
+        .source(source)
+        .build()
+        .map_err(|e| StoreError::internal_with_source("failed to rebuild stored snapshot", e))?;
+
+    snapshot.confidence = model.confidence;
+    snapshot.axes = axes;
+    snapshot.updated_at_unix_ms = model.created_at_unix_ms;
+
+    Ok(snapshot)
+}
+
+#[async_trait]
+impl StateStore for SqlStore {
+    #[tracing::instrument(skip(self, snapshot), fields(user_id = %snapshot.user_id))]
+    async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+        snapshot.validate()?;
+
+        let axes_json = serde_json::to_string(&snapshot.axes)
+            .map_err(|e| StoreError::internal_with_source("failed to serialize axes", e))?;
+
+        let row = entity::ActiveModel {
+            user_id: Set(snapshot.user_id.clone()),
+            source: Set(snapshot.source.to_string()),
+            confidence: Set(snapshot.confidence),
+            axes_json: Set(axes_json),
+            created_at_unix_ms: Set(snapshot.updated_at_unix_ms),
+            ..Default::default()
+        };
+
+        row.insert(&self.db)
+            .await
+            .map_err(|e| StoreError::internal_with_source("failed to insert snapshot", e))?;
+
+        tracing::debug!("upserted state snapshot");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), fields(user_id = %user_id))]
+    async fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
+        let row = StateSnapshotEntity::find()
+            .filter(entity::Column::UserId.eq(user_id))
+            .order_by_desc(entity::Column::CreatedAtUnixMs)
+            .order_by_desc(entity::Column::Id)
+            .one(&self.db)
+            .await
+            .map_err(|e| StoreError::internal_with_source("failed to query latest snapshot", e))?;
+
+        row.map(row_to_snapshot).transpose()
+    }
+
+    #[tracing::instrument(skip(self), fields(user_id = %user_id))]
+    async fn delete(&self, user_id: &str) -> Result<(), StoreError> {
+        StateSnapshotEntity::delete_many()
+            .filter(entity::Column::UserId.eq(user_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| StoreError::internal_with_source("failed to delete user state", e))?;
+
+        tracing::debug!("deleted user state");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), fields(user_id = %user_id, limit = %limit))]
+    async fn get_history(&self, user_id: &str, limit: usize) -> Result<Vec<StateSnapshot>, StoreError> {
+        let rows = StateSnapshotEntity::find()
+            .filter(entity::Column::UserId.eq(user_id))
+            .order_by_desc(entity::Column::CreatedAtUnixMs)
+            .order_by_desc(entity::Column::Id)
+            .limit(limit as u64)
+            .all(&self.db)
+            .await
+            .map_err(|e| StoreError::internal_with_source("failed to query history", e))?;
+
+        let snapshots = rows.into_iter().map(row_to_snapshot).collect::<Result<Vec<_>, _>>()?;
+        tracing::debug!(count = snapshots.len(), "retrieved history");
+        Ok(snapshots)
+    }
+
+    async fn health_check(&self) -> Result<bool, StoreError> {
+        self.db
+            .execute(Statement::from_string(
+                self.db.get_database_backend(),
+                "SELECT 1".to_string(),
+            ))
+            .await
+            .map(|_| true)
+            .map_err(|e| StoreError::connection(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl HealthCheck for SqlStore {
+    async fn check(&self) -> ComponentHealth {
+        match self.health_check().await {
+            Ok(true) => ComponentHealth::healthy("sql_store"),
+            Ok(false) | Err(_) => ComponentHealth::unhealthy("sql_store", "SELECT 1 failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_snapshot(user_id: &str) -> StateSnapshot {
+        StateSnapshot::builder()
+            .user_id(user_id)
+            .source(Source::SelfReport)
+            .axis("warmth", 0.7)
+            .build()
+            .unwrap()
+    }
+
+    async fn test_store() -> SqlStore {
+        SqlStore::new(SqlStoreConfig::default()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_upsert_and_get() {
+        let store = test_store().await;
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        let retrieved = store.get_latest("user_1").await.unwrap();
+        assert!(retrieved.is_some());
+        assert_eq!(retrieved.unwrap().user_id, "user_1");
+    }
+
+    #[tokio::test]
+    async fn test_get_nonexistent() {
+        let store = test_store().await;
+        assert!(store.get_latest("nonexistent").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_is_the_most_recent_row() {
+        let store = test_store().await;
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        let mut updated = test_snapshot("user_1");
+        updated.axes.insert("warmth".to_string(), 0.1);
+        store.upsert_latest(updated).await.unwrap();
+
+        let retrieved = store.get_latest("user_1").await.unwrap().unwrap();
+        assert_eq!(retrieved.axes["warmth"], 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_removes_all_rows_for_user() {
+        let store = test_store().await;
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        store.delete("user_1").await.unwrap();
+
+        assert!(store.get_latest("user_1").await.unwrap().is_none());
+        assert!(store.get_history("user_1", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_history_records_every_upsert() {
+        let store = test_store().await;
+
+        for i in 0..3 {
+            let mut snapshot = test_snapshot("user_1");
+            snapshot.axes.insert("warmth".to_string(), i as f32 / 10.0);
+            store.upsert_latest(snapshot).await.unwrap();
+        }
+
+        let history = store.get_history("user_1", 10).await.unwrap();
+        assert_eq!(history.len(), 3);
+        // Newest first.
+        assert_eq!(history[0].axes["warmth"], 0.2);
+        assert_eq!(history[2].axes["warmth"], 0.0);
+    }
+}
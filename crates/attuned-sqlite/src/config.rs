@@ -0,0 +1,36 @@
+//! Configuration for the SQLite store.
+
+use std::time::Duration;
+
+/// Configuration for connecting to and using the SQLite-backed store.
+#[derive(Clone, Debug)]
+pub struct SqliteStoreConfig {
+    /// SQLite connection string (e.g. `"sqlite://attuned.db"`,
+    /// `"sqlite::memory:"`).
+    pub database_url: String,
+
+    /// Maximum number of pooled connections.
+    pub max_connections: u32,
+
+    /// Whether to keep historical snapshots in `state_history`.
+    pub enable_history: bool,
+
+    /// Number of days to retain history rows (`None` = forever). Rows
+    /// older than the cutoff are pruned on each upsert.
+    pub history_retention_days: Option<u32>,
+
+    /// Connection timeout.
+    pub connect_timeout: Duration,
+}
+
+impl Default for SqliteStoreConfig {
+    fn default() -> Self {
+        Self {
+            database_url: "sqlite::memory:".to_string(),
+            max_connections: 5,
+            enable_history: false,
+            history_retention_days: None,
+            connect_timeout: Duration::from_secs(10),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! SQLite-specific error types.
+
+use thiserror::Error;
+
+/// Errors specific to the SQLite backend.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SqliteError {
+    /// A database operation (connect, query, migrate) failed.
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
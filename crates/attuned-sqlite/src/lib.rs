@@ -0,0 +1,38 @@
+//! # attuned-sqlite
+//!
+//! Durable, single-node SQLite storage backend for Attuned, built on `sqlx`.
+//!
+//! Unlike [`attuned_store::MemoryStore`], which loses all state on restart,
+//! [`SqliteStore`] persists the latest snapshot per user in `state_latest`
+//! and, when history is enabled, an append-only `state_history` log pruned
+//! by [`SqliteStoreConfig::history_retention_days`]. This gives single-node
+//! deployments durability without standing up Qdrant or a full SQL server.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use attuned_sqlite::{SqliteStore, SqliteStoreConfig};
+//! use attuned_store::StateStore;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let config = SqliteStoreConfig {
+//!         database_url: "sqlite://attuned.db".to_string(),
+//!         ..Default::default()
+//!     };
+//!
+//!     let store = SqliteStore::new(config).await?;
+//!     // Use store via StateStore trait...
+//!     Ok(())
+//! }
+//! ```
+
+#![deny(missing_docs)]
+
+mod config;
+mod error;
+mod store;
+
+pub use config::SqliteStoreConfig;
+pub use error::SqliteError;
+pub use store::SqliteStore;
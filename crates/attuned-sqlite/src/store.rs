@@ -0,0 +1,387 @@
+//! `sqlx`-backed `StateStore` implementation.
+
+use crate::config::SqliteStoreConfig;
+use crate::error::SqliteError;
+use async_trait::async_trait;
+use attuned_core::{ComponentHealth, HealthCheck, Source, StateSnapshot};
+use attuned_store::{StateStore, StoreError};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Durable [`StateStore`] backed by SQLite via `sqlx`, with a pooled
+/// connection and optional bounded history.
+///
+/// `state_latest` holds one row per user (the current snapshot);
+/// `state_history` is an append-only log ordered by a per-user `idx`, so
+/// `get_history` is a single indexed `ORDER BY idx DESC LIMIT` query.
+pub struct SqliteStore {
+    pool: SqlitePool,
+    config: SqliteStoreConfig,
+}
+
+impl SqliteStore {
+    /// Connect to `config.database_url` and ensure the backing tables exist.
+    pub async fn new(config: SqliteStoreConfig) -> Result<Self, SqliteError> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .acquire_timeout(config.connect_timeout)
+            .connect(&config.database_url)
+            .await?;
+
+        Self::migrate(&pool).await?;
+
+        Ok(Self { pool, config })
+    }
+
+    async fn migrate(pool: &SqlitePool) -> Result<(), SqliteError> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS state_latest (
+                user_id TEXT PRIMARY KEY,
+                snapshot TEXT NOT NULL,
+                observed_at BIGINT NOT NULL
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS state_history (
+                user_id TEXT NOT NULL,
+                idx INTEGER NOT NULL,
+                snapshot TEXT NOT NULL,
+                created_at BIGINT NOT NULL,
+                PRIMARY KEY (user_id, idx)
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_state_history_user_idx ON state_history (user_id, idx DESC)",
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove history rows older than `history_retention_days`, if configured.
+    async fn prune_history(&self, user_id: &str, now_unix_ms: i64) -> Result<(), StoreError> {
+        let Some(days) = self.config.history_retention_days else {
+            return Ok(());
+        };
+        let cutoff = now_unix_ms - i64::from(days) * DAY_MS;
+
+        sqlx::query("DELETE FROM state_history WHERE user_id = ? AND created_at < ?")
+            .bind(user_id)
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::internal_with_source("failed to prune history", e))?;
+
+        Ok(())
+    }
+}
+
+/// The plaintext contents of a snapshot as stored in the `snapshot` JSON
+/// column. `user_id` lives in its own column, so it's excluded here.
+#[derive(Serialize, Deserialize)]
+struct StoredSnapshot {
+    source: String,
+    confidence: f32,
+    axes: BTreeMap<String, f32>,
+    updated_at_unix_ms: i64,
+}
+
+fn snapshot_to_json(snapshot: &StateSnapshot) -> Result<String, StoreError> {
+    serde_json::to_string(&StoredSnapshot {
+        source: snapshot.source.to_string(),
+        confidence: snapshot.confidence,
+        axes: snapshot.axes.clone(),
+        updated_at_unix_ms: snapshot.updated_at_unix_ms,
+    })
+    .map_err(|e| StoreError::internal_with_source("failed to serialize snapshot", e))
+}
+
+fn json_to_snapshot(user_id: &str, json: &str) -> Result<StateSnapshot, StoreError> {
+    let stored: StoredSnapshot = serde_json::from_str(json)
+        .map_err(|e| StoreError::internal_with_source("failed to deserialize snapshot", e))?;
+    let source = parse_source(&stored.source)?;
+
+    let mut snapshot = StateSnapshot::builder()
+        .user_id(user_id)
+        .source(source)
+        .build()
+        .map_err(|e| StoreError::internal_with_source("failed to rebuild stored snapshot", e))?;
+
+    snapshot.confidence = stored.confidence;
+    snapshot.axes = stored.axes;
+    snapshot.updated_at_unix_ms = stored.updated_at_unix_ms;
+
+    Ok(snapshot)
+}
+
+fn parse_source(raw: &str) -> Result<Source, StoreError> {
+    match raw {
+        "self_report" => Ok(Source::SelfReport),
+        "inferred" => Ok(Source::Inferred),
+        "mixed" => Ok(Source::Mixed),
+        other => Err(StoreError::internal(format!("unknown stored source '{other}'"))),
+    }
+}
+
+#[async_trait]
+impl StateStore for SqliteStore {
+    #[tracing::instrument(skip(self, snapshot), fields(user_id = %snapshot.user_id))]
+    async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+        snapshot.validate()?;
+
+        let json = snapshot_to_json(&snapshot)?;
+        let observed_at = snapshot.updated_at_unix_ms;
+
+        sqlx::query(
+            r#"
+            INSERT INTO state_latest (user_id, snapshot, observed_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(user_id) DO UPDATE SET
+                snapshot = excluded.snapshot,
+                observed_at = excluded.observed_at
+            "#,
+        )
+        .bind(&snapshot.user_id)
+        .bind(&json)
+        .bind(observed_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::internal_with_source("failed to upsert latest snapshot", e))?;
+
+        if self.config.enable_history {
+            // Compute `idx` and insert in a single statement rather than a
+            // separate `SELECT MAX` followed by an `INSERT`, so two
+            // concurrent upserts for the same user can't compute the same
+            // `idx` and have the second fail its `(user_id, idx)` primary key.
+            sqlx::query(
+                r#"
+                INSERT INTO state_history (user_id, idx, snapshot, created_at)
+                SELECT ?, COALESCE(MAX(idx), 0) + 1, ?, ?
+                FROM state_history WHERE user_id = ?
+                "#,
+            )
+            .bind(&snapshot.user_id)
+            .bind(&json)
+            .bind(observed_at)
+            .bind(&snapshot.user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::internal_with_source("failed to append history row", e))?;
+
+            self.prune_history(&snapshot.user_id, observed_at).await?;
+        }
+
+        tracing::debug!("upserted state snapshot");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), fields(user_id = %user_id))]
+    async fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT snapshot FROM state_latest WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| StoreError::internal_with_source("failed to query latest snapshot", e))?;
+
+        row.map(|(json,)| json_to_snapshot(user_id, &json)).transpose()
+    }
+
+    #[tracing::instrument(skip(self), fields(user_id = %user_id))]
+    async fn delete(&self, user_id: &str) -> Result<(), StoreError> {
+        sqlx::query("DELETE FROM state_latest WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::internal_with_source("failed to delete latest snapshot", e))?;
+
+        sqlx::query("DELETE FROM state_history WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::internal_with_source("failed to delete history", e))?;
+
+        tracing::debug!("deleted user state");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self), fields(user_id = %user_id, limit = %limit))]
+    async fn get_history(
+        &self,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<StateSnapshot>, StoreError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT snapshot FROM state_history WHERE user_id = ? ORDER BY idx DESC LIMIT ?",
+        )
+        .bind(user_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::internal_with_source("failed to query history", e))?;
+
+        let snapshots = rows
+            .into_iter()
+            .map(|(json,)| json_to_snapshot(user_id, &json))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        tracing::debug!(count = snapshots.len(), "retrieved history");
+        Ok(snapshots)
+    }
+
+    async fn health_check(&self) -> Result<bool, StoreError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map(|_| true)
+            .map_err(|e| StoreError::connection(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl HealthCheck for SqliteStore {
+    async fn check(&self) -> ComponentHealth {
+        match self.health_check().await {
+            Ok(true) => ComponentHealth::healthy("sqlite_store"),
+            Ok(false) | Err(_) => ComponentHealth::unhealthy("sqlite_store", "SELECT 1 failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_snapshot(user_id: &str) -> StateSnapshot {
+        StateSnapshot::builder()
+            .user_id(user_id)
+            .source(Source::SelfReport)
+            .axis("warmth", 0.7)
+            .build()
+            .unwrap()
+    }
+
+    // A single pooled connection, so every query in a test sees the same
+    // in-memory database rather than each pooled connection getting its own
+    // private `:memory:` instance.
+    async fn test_store() -> SqliteStore {
+        let config = SqliteStoreConfig {
+            max_connections: 1,
+            ..SqliteStoreConfig::default()
+        };
+        SqliteStore::new(config).await.unwrap()
+    }
+
+    async fn test_store_with_history() -> SqliteStore {
+        let config = SqliteStoreConfig {
+            max_connections: 1,
+            enable_history: true,
+            ..SqliteStoreConfig::default()
+        };
+        SqliteStore::new(config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_upsert_and_get() {
+        let store = test_store().await;
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        let retrieved = store.get_latest("user_1").await.unwrap();
+        assert!(retrieved.is_some());
+        assert_eq!(retrieved.unwrap().user_id, "user_1");
+    }
+
+    #[tokio::test]
+    async fn test_get_nonexistent() {
+        let store = test_store().await;
+        assert!(store.get_latest("nonexistent").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_upsert_overwrites_latest() {
+        let store = test_store().await;
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        let mut updated = test_snapshot("user_1");
+        updated.axes.insert("warmth".to_string(), 0.1);
+        store.upsert_latest(updated).await.unwrap();
+
+        let retrieved = store.get_latest("user_1").await.unwrap().unwrap();
+        assert_eq!(retrieved.axes["warmth"], 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let store = test_store().await;
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        store.delete("user_1").await.unwrap();
+
+        assert!(store.get_latest("user_1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_history_disabled_by_default() {
+        let store = test_store().await;
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        assert!(store.get_history("user_1", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_history_records_each_upsert() {
+        let store = test_store_with_history().await;
+
+        for i in 0..3 {
+            let mut snapshot = test_snapshot("user_1");
+            snapshot.axes.insert("warmth".to_string(), i as f32 / 10.0);
+            store.upsert_latest(snapshot).await.unwrap();
+        }
+
+        let history = store.get_history("user_1", 10).await.unwrap();
+        assert_eq!(history.len(), 3);
+        // Newest first.
+        assert_eq!(history[0].axes["warmth"], 0.2);
+        assert_eq!(history[2].axes["warmth"], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_upserts_all_get_distinct_history_rows() {
+        use std::sync::Arc;
+
+        let store = Arc::new(test_store_with_history().await);
+
+        let mut handles = Vec::new();
+        for i in 0..10 {
+            let store = store.clone();
+            handles.push(tokio::spawn(async move {
+                let mut snapshot = test_snapshot("user_1");
+                snapshot.axes.insert("warmth".to_string(), i as f32 / 10.0);
+                store.upsert_latest(snapshot).await
+            }));
+        }
+
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        // Every concurrent upsert must land its own history row, rather than
+        // two racing on the same computed `idx` and one silently failing.
+        assert_eq!(store.get_history("user_1", 20).await.unwrap().len(), 10);
+    }
+}
@@ -0,0 +1,118 @@
+//! Synchronous facade over an async [`StateStore`].
+//!
+//! Not every consumer runs inside a Tokio runtime — CLI tools, scripts, and
+//! FFI callers usually don't. [`BlockingStore`] wraps any `S: StateStore` and
+//! exposes the same operations as plain, non-async methods, driving each
+//! call to completion on a dedicated current-thread runtime created once
+//! when the store is built (never one per call).
+
+use crate::error::StoreError;
+use crate::traits::StateStore;
+use attuned_core::StateSnapshot;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// Synchronous mirror of [`StateStore`].
+///
+/// Holds its own background runtime, so it's `Send + Sync` and can live
+/// behind a `static`/global without the caller managing an executor. Use
+/// this at the boundary of a synchronous embedding context; if you're
+/// already inside Tokio, use the wrapped store's async methods directly.
+pub struct BlockingStore<S> {
+    inner: Arc<S>,
+    runtime: Runtime,
+}
+
+impl<S: StateStore> BlockingStore<S> {
+    /// Wrap `inner`, spinning up the dedicated background runtime.
+    pub fn new(inner: S) -> Result<Self, StoreError> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| StoreError::internal_with_source("failed to start blocking runtime", e))?;
+
+        Ok(Self {
+            inner: Arc::new(inner),
+            runtime,
+        })
+    }
+
+    /// Insert or update the latest state snapshot for a user. See
+    /// [`StateStore::upsert_latest`].
+    pub fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+        self.runtime.block_on(self.inner.upsert_latest(snapshot))
+    }
+
+    /// Get the latest state snapshot for a user. See
+    /// [`StateStore::get_latest`].
+    pub fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
+        self.runtime.block_on(self.inner.get_latest(user_id))
+    }
+
+    /// Delete the state for a user. See [`StateStore::delete`].
+    pub fn delete(&self, user_id: &str) -> Result<(), StoreError> {
+        self.runtime.block_on(self.inner.delete(user_id))
+    }
+
+    /// Get historical snapshots for a user. See [`StateStore::get_history`].
+    pub fn get_history(&self, user_id: &str, limit: usize) -> Result<Vec<StateSnapshot>, StoreError> {
+        self.runtime.block_on(self.inner.get_history(user_id, limit))
+    }
+
+    /// Check if the store is healthy and can accept requests. See
+    /// [`StateStore::health_check`].
+    pub fn health_check(&self) -> Result<bool, StoreError> {
+        self.runtime.block_on(self.inner.health_check())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryStore, MemoryStoreConfig};
+    use attuned_core::{Source, StateSnapshot};
+
+    fn test_snapshot(user_id: &str) -> StateSnapshot {
+        StateSnapshot::builder()
+            .user_id(user_id)
+            .source(Source::SelfReport)
+            .axis("warmth", 0.5)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_upsert_and_get() {
+        let store = BlockingStore::new(MemoryStore::new(MemoryStoreConfig::default())).unwrap();
+        store.upsert_latest(test_snapshot("user_1")).unwrap();
+
+        let retrieved = store.get_latest("user_1").unwrap();
+        assert_eq!(retrieved.unwrap().user_id, "user_1");
+    }
+
+    #[test]
+    fn test_get_nonexistent() {
+        let store = BlockingStore::new(MemoryStore::new(MemoryStoreConfig::default())).unwrap();
+        assert!(store.get_latest("nobody").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_delete() {
+        let store = BlockingStore::new(MemoryStore::new(MemoryStoreConfig::default())).unwrap();
+        store.upsert_latest(test_snapshot("user_1")).unwrap();
+        store.delete("user_1").unwrap();
+        assert!(store.get_latest("user_1").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_health_check() {
+        let store = BlockingStore::new(MemoryStore::new(MemoryStoreConfig::default())).unwrap();
+        assert!(store.health_check().unwrap());
+    }
+
+    #[test]
+    fn test_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<BlockingStore<MemoryStore>>();
+    }
+}
@@ -0,0 +1,306 @@
+//! Transparent at-rest encryption wrapper for any [`StateStore`].
+//!
+//! Wraps an inner store so every snapshot is sealed with
+//! XChaCha20-Poly1305 before it reaches the inner store and opened again
+//! on read. The inner store only ever sees an opaque byte payload packed
+//! into a `StateSnapshot`-compatible envelope — its axes map carries raw
+//! ciphertext bytes rather than affective state — so `MemoryStore` and any
+//! future remote backend get encryption for free with no awareness of it.
+
+use crate::error::StoreError;
+use crate::traits::StateStore;
+use async_trait::async_trait;
+use attuned_core::{ComponentHealth, HealthCheck, Source, StateSnapshot};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const NONCE_LEN: usize = 24;
+const PAYLOAD_AXIS_PREFIX: &str = "__enc";
+
+/// Key material accepted by [`EncryptedStore::new`].
+pub enum EncryptionKey {
+    /// A raw 256-bit key, used as-is.
+    Raw([u8; 32]),
+    /// A passphrase and salt to derive a 256-bit key from via Argon2id.
+    Passphrase {
+        /// The passphrase to derive the key from.
+        passphrase: String,
+        /// Salt for the key derivation (at least 8 bytes, unique per deployment).
+        salt: Vec<u8>,
+    },
+}
+
+impl EncryptionKey {
+    fn derive(&self) -> Result<[u8; 32], StoreError> {
+        match self {
+            EncryptionKey::Raw(key) => Ok(*key),
+            EncryptionKey::Passphrase { passphrase, salt } => {
+                let mut key = [0u8; 32];
+                argon2::Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|e| StoreError::internal(format!("key derivation failed: {e}")))?;
+                Ok(key)
+            }
+        }
+    }
+}
+
+/// The real, plaintext contents of a [`StateSnapshot`], serialized and
+/// sealed as a unit. `user_id` is deliberately excluded: it stays
+/// plaintext on the envelope so the inner store can still index by it,
+/// and is bound into the AEAD associated data instead.
+#[derive(Serialize, Deserialize)]
+struct PlaintextPayload {
+    source: String,
+    confidence: f32,
+    axes: BTreeMap<String, f32>,
+    updated_at_unix_ms: i64,
+}
+
+/// Wraps an inner [`StateStore`], transparently encrypting snapshot
+/// contents at rest with XChaCha20-Poly1305.
+///
+/// Each record gets a fresh random 24-byte nonce, prepended to the
+/// ciphertext. The AEAD associated data is the `user_id`, so ciphertext
+/// sealed for one user fails to decrypt under another user's record even
+/// if the inner store's rows were swapped or copied.
+pub struct EncryptedStore<S: StateStore> {
+    inner: S,
+    cipher: XChaCha20Poly1305,
+}
+
+impl<S: StateStore> EncryptedStore<S> {
+    /// Wrap `inner`, sealing every snapshot with `key`.
+    pub fn new(inner: S, key: EncryptionKey) -> Result<Self, StoreError> {
+        let key_bytes = key.derive()?;
+        let cipher = XChaCha20Poly1305::new((&key_bytes).into());
+        Ok(Self { inner, cipher })
+    }
+
+    fn seal(&self, snapshot: &StateSnapshot) -> Result<StateSnapshot, StoreError> {
+        let payload = PlaintextPayload {
+            source: snapshot.source.to_string(),
+            confidence: snapshot.confidence,
+            axes: snapshot.axes.clone(),
+            updated_at_unix_ms: snapshot.updated_at_unix_ms,
+        };
+        let plaintext = serde_json::to_vec(&payload)
+            .map_err(|e| StoreError::internal(format!("failed to serialize snapshot: {e}")))?;
+
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: snapshot.user_id.as_bytes(),
+                },
+            )
+            .map_err(|_| StoreError::internal("failed to seal state snapshot"))?;
+
+        let mut sealed_bytes = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed_bytes.extend_from_slice(nonce.as_slice());
+        sealed_bytes.extend_from_slice(&ciphertext);
+
+        Ok(StateSnapshot {
+            user_id: snapshot.user_id.clone(),
+            source: Source::Inferred,
+            confidence: 0.0,
+            axes: encode_payload(&sealed_bytes),
+            updated_at_unix_ms: snapshot.updated_at_unix_ms,
+        })
+    }
+
+    fn open(&self, sealed: &StateSnapshot) -> Result<StateSnapshot, StoreError> {
+        let decryption_failed = || StoreError::Decryption {
+            user_id: sealed.user_id.clone(),
+        };
+
+        let sealed_bytes = decode_payload(&sealed.axes).map_err(|_| decryption_failed())?;
+        if sealed_bytes.len() < NONCE_LEN {
+            return Err(decryption_failed());
+        }
+        let (nonce_bytes, ciphertext) = sealed_bytes.split_at(NONCE_LEN);
+        let nonce = XNonce::from_slice(nonce_bytes);
+
+        let plaintext = self
+            .cipher
+            .decrypt(
+                nonce,
+                Payload {
+                    msg: ciphertext,
+                    aad: sealed.user_id.as_bytes(),
+                },
+            )
+            .map_err(|_| decryption_failed())?;
+
+        let payload: PlaintextPayload =
+            serde_json::from_slice(&plaintext).map_err(|_| decryption_failed())?;
+
+        Ok(StateSnapshot {
+            user_id: sealed.user_id.clone(),
+            source: parse_source(&payload.source).map_err(|_| decryption_failed())?,
+            confidence: payload.confidence,
+            axes: payload.axes,
+            updated_at_unix_ms: payload.updated_at_unix_ms,
+        })
+    }
+}
+
+/// Pack raw bytes into an axes map, one byte per axis, keyed by a
+/// zero-padded index so `BTreeMap`'s natural ordering reconstructs the
+/// byte sequence. Every byte value (0-255) round-trips exactly through
+/// `f32`.
+fn encode_payload(bytes: &[u8]) -> BTreeMap<String, f32> {
+    bytes
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (format!("{PAYLOAD_AXIS_PREFIX}:{i:010}"), *b as f32))
+        .collect()
+}
+
+fn decode_payload(axes: &BTreeMap<String, f32>) -> Result<Vec<u8>, ()> {
+    axes.values()
+        .map(|value| {
+            let rounded = value.round();
+            if (0.0..=255.0).contains(&rounded) {
+                Ok(rounded as u8)
+            } else {
+                Err(())
+            }
+        })
+        .collect()
+}
+
+fn parse_source(raw: &str) -> Result<Source, ()> {
+    match raw {
+        "self_report" => Ok(Source::SelfReport),
+        "inferred" => Ok(Source::Inferred),
+        "mixed" => Ok(Source::Mixed),
+        _ => Err(()),
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> StateStore for EncryptedStore<S> {
+    async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+        snapshot.validate()?;
+        let sealed = self.seal(&snapshot)?;
+        self.inner.upsert_latest(sealed).await
+    }
+
+    async fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
+        match self.inner.get_latest(user_id).await? {
+            Some(sealed) => Ok(Some(self.open(&sealed)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete(&self, user_id: &str) -> Result<(), StoreError> {
+        self.inner.delete(user_id).await
+    }
+
+    async fn get_history(
+        &self,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<StateSnapshot>, StoreError> {
+        self.inner
+            .get_history(user_id, limit)
+            .await?
+            .into_iter()
+            .map(|sealed| self.open(&sealed))
+            .collect()
+    }
+
+    async fn health_check(&self) -> Result<bool, StoreError> {
+        self.inner.health_check().await
+    }
+}
+
+#[async_trait]
+impl<S: StateStore + HealthCheck> HealthCheck for EncryptedStore<S> {
+    async fn check(&self) -> ComponentHealth {
+        self.inner.check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryStore;
+
+    fn test_snapshot(user_id: &str) -> StateSnapshot {
+        StateSnapshot::builder()
+            .user_id(user_id)
+            .source(Source::SelfReport)
+            .axis("warmth", 0.7)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_roundtrip_through_encryption() {
+        let store = EncryptedStore::new(MemoryStore::default(), EncryptionKey::Raw([7u8; 32])).unwrap();
+
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        let retrieved = store.get_latest("user_1").await.unwrap().unwrap();
+        assert_eq!(retrieved.user_id, "user_1");
+        assert_eq!(retrieved.axes["warmth"], 0.7);
+    }
+
+    #[tokio::test]
+    async fn test_inner_store_never_sees_plaintext_axes() {
+        let inner = MemoryStore::default();
+        let store = EncryptedStore::new(inner.clone(), EncryptionKey::Raw([1u8; 32])).unwrap();
+
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        let raw = inner.get_latest("user_1").await.unwrap().unwrap();
+        assert!(!raw.axes.contains_key("warmth"));
+        assert!(raw.axes.keys().all(|k| k.starts_with(PAYLOAD_AXIS_PREFIX)));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_key_fails_with_decryption_error() {
+        let inner = MemoryStore::default();
+        let writer = EncryptedStore::new(inner.clone(), EncryptionKey::Raw([1u8; 32])).unwrap();
+        let reader = EncryptedStore::new(inner, EncryptionKey::Raw([2u8; 32])).unwrap();
+
+        writer.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        let result = reader.get_latest("user_1").await;
+        assert!(matches!(result, Err(StoreError::Decryption { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_ciphertext_cannot_be_replayed_under_another_user() {
+        let inner = MemoryStore::default();
+        let store = EncryptedStore::new(inner.clone(), EncryptionKey::Raw([3u8; 32])).unwrap();
+
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+        let mut sealed = inner.get_latest("user_1").await.unwrap().unwrap();
+        sealed.user_id = "user_2".to_string();
+        inner.upsert_latest(sealed).await.unwrap();
+
+        let result = store.get_latest("user_2").await;
+        assert!(matches!(result, Err(StoreError::Decryption { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_passphrase_derived_key_roundtrips() {
+        let key = EncryptionKey::Passphrase {
+            passphrase: "correct horse battery staple".to_string(),
+            salt: b"attuned-test-salt".to_vec(),
+        };
+        let store = EncryptedStore::new(MemoryStore::default(), key).unwrap();
+
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+        let retrieved = store.get_latest("user_1").await.unwrap().unwrap();
+        assert_eq!(retrieved.axes["warmth"], 0.7);
+    }
+}
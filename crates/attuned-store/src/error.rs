@@ -36,6 +36,15 @@ pub enum StoreError {
     /// Validation error.
     #[error("validation error: {0}")]
     Validation(#[from] attuned_core::ValidationError),
+
+    /// Decryption or authentication failure while opening an encrypted
+    /// snapshot (e.g. wrong key, corrupted ciphertext, or a record moved
+    /// to a different user).
+    #[error("failed to decrypt state for user: {user_id}")]
+    Decryption {
+        /// The user ID whose encrypted record failed to decrypt.
+        user_id: String,
+    },
 }
 
 impl StoreError {
@@ -33,10 +33,20 @@
 
 #![deny(missing_docs)]
 
+#[cfg(feature = "blocking")]
+mod blocking;
+mod encryption;
 mod error;
 mod memory;
+mod pooled;
+mod replication;
 mod traits;
 
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingStore;
+pub use encryption::{EncryptedStore, EncryptionKey};
 pub use error::StoreError;
 pub use memory::{MemoryStore, MemoryStoreConfig};
+pub use pooled::{PoolManager, PooledConnection, PooledStore, PooledStoreConfig, RetryConfig};
+pub use replication::{MemoryRecordStore, Record, RecordSink, RecordStore, ReplicatedStore};
 pub use traits::StateStore;
@@ -3,18 +3,28 @@
 use crate::error::StoreError;
 use crate::traits::StateStore;
 use async_trait::async_trait;
-use attuned_core::{ComponentHealth, HealthCheck, StateSnapshot};
+use attuned_core::{ComponentHealth, HealthCheck, Source, StateSnapshot};
 use dashmap::DashMap;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::Arc;
 
+/// Default number of upserts between full history checkpoints.
+pub const KEEP_STATE_EVERY: usize = 64;
+
 /// Configuration for the in-memory store.
 #[derive(Clone, Debug)]
 pub struct MemoryStoreConfig {
     /// Whether to keep historical snapshots.
     pub enable_history: bool,
-    /// Maximum number of historical snapshots per user.
+    /// Maximum number of historical points to retain per user.
+    ///
+    /// Enforced in whole checkpoint+patch segments: a segment is only ever
+    /// dropped as a unit, so the retained count can exceed this when the
+    /// oldest live segment is itself longer than the configured max.
     pub max_history_per_user: usize,
+    /// Number of upserts between full checkpoint snapshots. Between
+    /// checkpoints, only the diff from the previous point is stored.
+    pub checkpoint_interval: usize,
 }
 
 impl Default for MemoryStoreConfig {
@@ -22,8 +32,152 @@ impl Default for MemoryStoreConfig {
         Self {
             enable_history: false,
             max_history_per_user: 100,
+            checkpoint_interval: KEEP_STATE_EVERY,
+        }
+    }
+}
+
+/// The diff between one historical point and the next.
+///
+/// Only axes that changed or were removed are recorded; everything else
+/// (source, confidence, timestamp) is cheap to store in full.
+#[derive(Clone, Debug, PartialEq)]
+struct StatePatch {
+    /// Axes that were added or changed, with their new values.
+    changed_axes: BTreeMap<String, f32>,
+    /// Axes that were present before this point and are no longer set.
+    removed_axes: Vec<String>,
+    source: Source,
+    confidence: f32,
+    updated_at_unix_ms: i64,
+}
+
+/// One entry in a user's history log: either a full snapshot or a patch
+/// against the point immediately before it.
+#[derive(Clone, Debug)]
+enum HistoryEntry {
+    Checkpoint(StateSnapshot),
+    Patch(StatePatch),
+}
+
+/// A user's history log, newest entry at the front.
+///
+/// The back of `entries` is always a [`HistoryEntry::Checkpoint`] — the
+/// first upsert for a user is always a checkpoint, and trimming only ever
+/// removes whole segments, so this invariant never breaks.
+#[derive(Default)]
+struct UserHistory {
+    entries: VecDeque<HistoryEntry>,
+    /// Upserts applied since the most recent checkpoint.
+    since_checkpoint: usize,
+}
+
+impl UserHistory {
+    fn push(&mut self, prev: Option<&StateSnapshot>, next: &StateSnapshot, checkpoint_interval: usize) {
+        let is_checkpoint = prev.is_none() || self.since_checkpoint + 1 >= checkpoint_interval.max(1);
+
+        if is_checkpoint {
+            self.entries.push_front(HistoryEntry::Checkpoint(next.clone()));
+            self.since_checkpoint = 0;
+        } else {
+            let patch = diff_snapshot(prev.expect("checked above"), next);
+            self.entries.push_front(HistoryEntry::Patch(patch));
+            self.since_checkpoint += 1;
+        }
+    }
+
+    /// Drop whole oldest segments until `entries.len() <= max`, where
+    /// possible. A single segment longer than `max` is kept intact rather
+    /// than dropping its checkpoint and orphaning the patches after it.
+    fn trim(&mut self, max: usize) {
+        while self.entries.len() > max {
+            // The back of the deque is always a checkpoint (a segment
+            // boundary). Walk forward from it counting patches until the
+            // next checkpoint (or the front) to find the oldest segment's
+            // length.
+            let mut segment_len = 1;
+            for entry in self.entries.iter().rev().skip(1) {
+                match entry {
+                    HistoryEntry::Patch(_) => segment_len += 1,
+                    HistoryEntry::Checkpoint(_) => break,
+                }
+            }
+
+            if self.entries.len() <= segment_len {
+                break;
+            }
+
+            for _ in 0..segment_len {
+                self.entries.pop_back();
+            }
+        }
+    }
+
+    /// Reconstruct up to `limit` historical points, newest first.
+    fn reconstruct(&self, limit: usize) -> Vec<StateSnapshot> {
+        let mut forward = Vec::with_capacity(self.entries.len());
+        let mut current: Option<StateSnapshot> = None;
+
+        for entry in self.entries.iter().rev() {
+            let snapshot = match entry {
+                HistoryEntry::Checkpoint(s) => s.clone(),
+                HistoryEntry::Patch(patch) => {
+                    apply_patch(current.as_ref().expect("patch without preceding checkpoint"), patch)
+                }
+            };
+            current = Some(snapshot.clone());
+            forward.push(snapshot);
+        }
+
+        forward.reverse();
+        forward.truncate(limit);
+        forward
+    }
+}
+
+fn diff_snapshot(prev: &StateSnapshot, next: &StateSnapshot) -> StatePatch {
+    let mut changed_axes = BTreeMap::new();
+    for (axis, value) in &next.axes {
+        match prev.axes.get(axis) {
+            Some(prev_value) if prev_value == value => {}
+            _ => {
+                changed_axes.insert(axis.clone(), *value);
+            }
         }
     }
+
+    let removed_axes = prev
+        .axes
+        .keys()
+        .filter(|axis| !next.axes.contains_key(axis.as_str()))
+        .cloned()
+        .collect();
+
+    StatePatch {
+        changed_axes,
+        removed_axes,
+        source: next.source.clone(),
+        confidence: next.confidence,
+        updated_at_unix_ms: next.updated_at_unix_ms,
+    }
+}
+
+fn apply_patch(prev: &StateSnapshot, patch: &StatePatch) -> StateSnapshot {
+    let mut axes = prev.axes.clone();
+    for axis in &patch.removed_axes {
+        axes.remove(axis);
+    }
+    for (axis, value) in &patch.changed_axes {
+        axes.insert(axis.clone(), *value);
+    }
+
+    StateSnapshot {
+        user_id: prev.user_id.clone(),
+        source: patch.source.clone(),
+        confidence: patch.confidence,
+        axes,
+        updated_at_unix_ms: patch.updated_at_unix_ms,
+    }
 }
 
 /// Thread-safe in-memory state store.
@@ -33,7 +187,7 @@ impl Default for MemoryStoreConfig {
 #[derive(Clone)]
 pub struct MemoryStore {
     latest: Arc<DashMap<String, StateSnapshot>>,
-    history: Option<Arc<DashMap<String, VecDeque<StateSnapshot>>>>,
+    history: Option<Arc<DashMap<String, UserHistory>>>,
     config: MemoryStoreConfig,
 }
 
@@ -78,36 +232,46 @@ impl Default for MemoryStore {
     }
 }
 
-#[async_trait]
-impl StateStore for MemoryStore {
-    #[tracing::instrument(skip(self, snapshot), fields(user_id = %snapshot.user_id))]
-    async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
-        // Validate the snapshot
+impl MemoryStore {
+    /// Validate and apply a single upsert against the shared maps.
+    ///
+    /// Synchronous on purpose: `DashMap` access never awaits, so batch
+    /// callers can apply many upserts back-to-back without paying for a
+    /// fresh async dispatch per item.
+    fn upsert_one(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
         snapshot.validate()?;
 
         let user_id = snapshot.user_id.clone();
+        let previous = self.latest.get(&user_id).map(|r| r.value().clone());
 
-        // Store in history if enabled
         if let Some(ref history) = self.history {
-            let mut entry = history.entry(user_id.clone()).or_insert_with(VecDeque::new);
-            entry.push_front(snapshot.clone());
-
-            // Trim to max history size
-            while entry.len() > self.config.max_history_per_user {
-                entry.pop_back();
-            }
+            let mut entry = history.entry(user_id.clone()).or_default();
+            entry.push(previous.as_ref(), &snapshot, self.config.checkpoint_interval);
+            entry.trim(self.config.max_history_per_user);
         }
 
-        // Store as latest
         self.latest.insert(user_id, snapshot);
-
-        tracing::debug!("upserted state snapshot");
         Ok(())
     }
 
+    /// Look up a single user's latest snapshot against the shared map.
+    fn get_one(&self, user_id: &str) -> Option<StateSnapshot> {
+        self.latest.get(user_id).map(|r| r.value().clone())
+    }
+}
+
+#[async_trait]
+impl StateStore for MemoryStore {
+    #[tracing::instrument(skip(self, snapshot), fields(user_id = %snapshot.user_id))]
+    async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+        let result = self.upsert_one(snapshot);
+        tracing::debug!(ok = result.is_ok(), "upserted state snapshot");
+        result
+    }
+
     #[tracing::instrument(skip(self), fields(user_id = %user_id))]
     async fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
-        let result = self.latest.get(user_id).map(|r| r.value().clone());
+        let result = self.get_one(user_id);
         tracing::debug!(found = result.is_some(), "retrieved state snapshot");
         Ok(result)
     }
@@ -131,7 +295,7 @@ impl StateStore for MemoryStore {
         let result = match &self.history {
             Some(history) => history
                 .get(user_id)
-                .map(|entry| entry.iter().take(limit).cloned().collect())
+                .map(|entry| entry.reconstruct(limit))
                 .unwrap_or_default(),
             None => vec![],
         };
@@ -142,6 +306,25 @@ impl StateStore for MemoryStore {
     async fn health_check(&self) -> Result<bool, StoreError> {
         Ok(true)
     }
+
+    #[tracing::instrument(skip(self, snapshots), fields(count = snapshots.len()))]
+    async fn upsert_many(&self, snapshots: Vec<StateSnapshot>) -> Vec<Result<(), StoreError>> {
+        snapshots
+            .into_iter()
+            .map(|snapshot| self.upsert_one(snapshot))
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self, user_ids), fields(count = user_ids.len()))]
+    async fn get_many(
+        &self,
+        user_ids: &[String],
+    ) -> Vec<Result<Option<StateSnapshot>, StoreError>> {
+        user_ids
+            .iter()
+            .map(|user_id| Ok(self.get_one(user_id)))
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -199,6 +382,7 @@ mod tests {
         let config = MemoryStoreConfig {
             enable_history: true,
             max_history_per_user: 5,
+            ..MemoryStoreConfig::default()
         };
         let store = MemoryStore::new(config);
 
@@ -211,17 +395,67 @@ mod tests {
 
         let history = store.get_history("user_1", 10).await.unwrap();
         assert_eq!(history.len(), 3);
+        // Newest first, and each point reconstructs the axis value set at
+        // that time.
+        assert_eq!(history[0].axes["warmth"], 0.2);
+        assert_eq!(history[2].axes["warmth"], 0.0);
     }
 
     #[tokio::test]
-    async fn test_history_limit() {
+    async fn test_first_upsert_is_always_a_checkpoint() {
+        // Even with a checkpoint interval of 100 (so the first upsert is
+        // not otherwise "due" for one), the very first point for a user
+        // must still be a full checkpoint, not a patch with nothing to
+        // diff against.
+        let config = MemoryStoreConfig {
+            enable_history: true,
+            max_history_per_user: 100,
+            checkpoint_interval: 100,
+        };
+        let store = MemoryStore::new(config);
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+
+        let history = store.get_history("user_1", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].axes["warmth"], 0.7);
+    }
+
+    #[tokio::test]
+    async fn test_history_limit_trims_whole_segments() {
+        // checkpoint_interval = 2 means each segment is [checkpoint, patch],
+        // so trimming to max_history_per_user = 3 drops exactly the oldest
+        // whole segment (2 entries) from 5, leaving 3.
         let config = MemoryStoreConfig {
             enable_history: true,
             max_history_per_user: 3,
+            checkpoint_interval: 2,
+        };
+        let store = MemoryStore::new(config);
+
+        for i in 0..5 {
+            let mut snapshot = test_snapshot("user_1");
+            snapshot.axes.insert("warmth".to_string(), i as f32 / 10.0);
+            store.upsert_latest(snapshot).await.unwrap();
+        }
+
+        let history = store.get_history("user_1", 10).await.unwrap();
+        assert_eq!(history.len(), 3);
+        // The newest point is always reconstructable and correct.
+        assert_eq!(history[0].axes["warmth"], 0.4);
+    }
+
+    #[tokio::test]
+    async fn test_history_segment_trim_never_breaks_dependency() {
+        // A single oversized segment (checkpoint_interval larger than
+        // max_history_per_user) is kept intact rather than dropping its
+        // checkpoint and leaving orphaned patches.
+        let config = MemoryStoreConfig {
+            enable_history: true,
+            max_history_per_user: 2,
+            checkpoint_interval: 10,
         };
         let store = MemoryStore::new(config);
 
-        // Insert more than max
         for i in 0..5 {
             let mut snapshot = test_snapshot("user_1");
             snapshot.axes.insert("warmth".to_string(), i as f32 / 10.0);
@@ -229,7 +463,35 @@ mod tests {
         }
 
         let history = store.get_history("user_1", 10).await.unwrap();
-        assert_eq!(history.len(), 3); // Limited to max
+        // All 5 points belong to one still-open segment, so none are
+        // dropped even though max_history_per_user is 2.
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[0].axes["warmth"], 0.4);
+        assert_eq!(history[4].axes["warmth"], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_history_reconstruction_is_deterministic() {
+        let config = MemoryStoreConfig {
+            enable_history: true,
+            max_history_per_user: 100,
+            checkpoint_interval: 3,
+        };
+        let store = MemoryStore::new(config);
+
+        for i in 0..7 {
+            let mut snapshot = test_snapshot("user_1");
+            snapshot.axes.insert("warmth".to_string(), i as f32 / 10.0);
+            store.upsert_latest(snapshot).await.unwrap();
+        }
+
+        let first_read = store.get_history("user_1", 10).await.unwrap();
+        let second_read = store.get_history("user_1", 10).await.unwrap();
+        assert_eq!(first_read.len(), second_read.len());
+        for (a, b) in first_read.iter().zip(second_read.iter()) {
+            assert_eq!(a.axes, b.axes);
+            assert_eq!(a.updated_at_unix_ms, b.updated_at_unix_ms);
+        }
     }
 
     #[tokio::test]
@@ -253,4 +515,37 @@ mod tests {
 
         assert_eq!(store.len(), 100);
     }
+
+    #[tokio::test]
+    async fn test_upsert_many_reports_per_item_results() {
+        let store = MemoryStore::default();
+        let mut bad = test_snapshot("user_bad");
+        bad.user_id = String::new(); // invalid, should fail validation
+
+        let snapshots = vec![test_snapshot("user_1"), bad, test_snapshot("user_2")];
+        let results = store.upsert_many(snapshots).await;
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+        assert_eq!(store.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_many_preserves_order_and_missing_users() {
+        let store = MemoryStore::default();
+        store.upsert_latest(test_snapshot("user_1")).await.unwrap();
+        store.upsert_latest(test_snapshot("user_2")).await.unwrap();
+
+        let ids = vec![
+            "user_1".to_string(),
+            "nonexistent".to_string(),
+            "user_2".to_string(),
+        ];
+        let results = store.get_many(&ids).await;
+
+        assert_eq!(results[0].as_ref().unwrap().as_ref().unwrap().user_id, "user_1");
+        assert!(results[1].as_ref().unwrap().is_none());
+        assert_eq!(results[2].as_ref().unwrap().as_ref().unwrap().user_id, "user_2");
+    }
 }
@@ -0,0 +1,343 @@
+//! Pooled, auto-retrying wrapper for remote [`StateStore`] backends.
+//!
+//! Exercises the [`StoreError::Connection`] variant: connections are
+//! checked out of a `deadpool`-style async pool, health-checked on
+//! checkout, and transient failures are retried with bounded exponential
+//! backoff before surfacing a connection error with the underlying cause
+//! attached as `source`.
+
+use crate::error::StoreError;
+use crate::traits::StateStore;
+use async_trait::async_trait;
+use attuned_core::{ComponentHealth, HealthCheck, StateSnapshot};
+use deadpool::managed::{Manager, Pool, RecycleError, RecycleResult};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Configuration for [`PooledStore`].
+#[derive(Clone, Debug)]
+pub struct PooledStoreConfig {
+    /// Maximum number of pooled connections.
+    pub max_size: usize,
+    /// How long to wait for a connection before giving up.
+    pub acquire_timeout: Duration,
+    /// Retry policy applied to transient connection failures.
+    pub retry: RetryConfig,
+}
+
+impl Default for PooledStoreConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            acquire_timeout: Duration::from_secs(5),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+/// Bounded exponential backoff policy for retrying transient connection failures.
+#[derive(Clone, Debug)]
+pub struct RetryConfig {
+    /// Delay before the first retry.
+    pub base_delay: Duration,
+    /// Maximum number of retries before giving up.
+    pub max_retries: u32,
+    /// Maximum jitter added to each delay; the fraction of it applied is
+    /// deterministic and varies by attempt (no random source).
+    pub jitter: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(50),
+            max_retries: 3,
+            jitter: Duration::from_millis(25),
+        }
+    }
+}
+
+impl RetryConfig {
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX));
+        let jitter_fraction = (attempt as f64 * 0.37).fract(); // deterministic, no RNG dependency
+        exp + Duration::from_secs_f64(self.jitter.as_secs_f64() * jitter_fraction)
+    }
+}
+
+/// A connection produced by a [`PoolManager`], usable as a [`StateStore`].
+pub trait PooledConnection: StateStore + HealthCheck {}
+impl<T: StateStore + HealthCheck> PooledConnection for T {}
+
+/// Produces and recycles connections for [`PooledStore`].
+///
+/// Implement this for a given remote backend (e.g. a Qdrant or SQL client)
+/// to get pooling, retry, and degraded-health reporting for free.
+#[async_trait]
+pub trait PoolManager: Send + Sync + 'static {
+    /// The connection type this manager produces.
+    type Connection: PooledConnection + Send + Sync + 'static;
+
+    /// Open a new connection.
+    async fn create(&self) -> Result<Self::Connection, StoreError>;
+
+    /// Check whether an existing connection is still usable.
+    async fn is_healthy(&self, conn: &Self::Connection) -> bool {
+        conn.health_check().await.unwrap_or(false)
+    }
+}
+
+/// Adapts a [`PoolManager`] to `deadpool`'s [`Manager`] trait.
+struct DeadpoolAdapter<M: PoolManager>(Arc<M>);
+
+#[async_trait]
+impl<M: PoolManager> Manager for DeadpoolAdapter<M> {
+    type Type = M::Connection;
+    type Error = StoreError;
+
+    async fn create(&self) -> Result<Self::Type, Self::Error> {
+        self.0.create().await
+    }
+
+    async fn recycle(
+        &self,
+        conn: &mut Self::Type,
+        _metrics: &deadpool::managed::Metrics,
+    ) -> RecycleResult<Self::Error> {
+        if self.0.is_healthy(conn).await {
+            Ok(())
+        } else {
+            Err(RecycleError::Message(
+                "connection failed health check on checkout".into(),
+            ))
+        }
+    }
+}
+
+/// A [`StateStore`] backed by a pool of connections to a remote backend,
+/// with automatic retry on transient connection failures.
+pub struct PooledStore<M: PoolManager> {
+    pool: Pool<DeadpoolAdapter<M>>,
+    config: PooledStoreConfig,
+    recent_failures: AtomicU64,
+}
+
+impl<M: PoolManager> PooledStore<M> {
+    /// Create a new pooled store from a manager and configuration.
+    pub fn new(manager: M, config: PooledStoreConfig) -> Result<Self, StoreError> {
+        let pool = Pool::builder(DeadpoolAdapter(Arc::new(manager)))
+            .max_size(config.max_size)
+            .build()
+            .map_err(|e| StoreError::connection(format!("failed to build connection pool: {e}")))?;
+
+        Ok(Self {
+            pool,
+            config,
+            recent_failures: AtomicU64::new(0),
+        })
+    }
+
+    /// Run `op` against a checked-out connection, retrying with bounded
+    /// exponential backoff on `StoreError::Connection`.
+    async fn with_retry<T, F, Fut>(&self, op: F) -> Result<T, StoreError>
+    where
+        F: Fn(deadpool::managed::Object<DeadpoolAdapter<M>>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, StoreError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let conn = tokio::time::timeout(self.config.acquire_timeout, self.pool.get())
+                .await
+                .map_err(|_| StoreError::connection("timed out acquiring pooled connection"))?
+                .map_err(|e| {
+                    self.recent_failures.fetch_add(1, Ordering::Relaxed);
+                    StoreError::connection(format!("failed to acquire pooled connection: {e}"))
+                })?;
+
+            match op(conn).await {
+                Ok(value) => {
+                    self.recent_failures.store(0, Ordering::Relaxed);
+                    return Ok(value);
+                }
+                Err(StoreError::Connection { message, source }) if attempt < self.config.retry.max_retries => {
+                    self.recent_failures.fetch_add(1, Ordering::Relaxed);
+                    tracing::warn!(attempt, %message, "transient store error, retrying");
+                    tokio::time::sleep(self.config.retry.delay_for(attempt)).await;
+                    attempt += 1;
+                    let _ = source;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Whether the pool is currently exhausted (no spare capacity).
+    pub fn is_saturated(&self) -> bool {
+        let status = self.pool.status();
+        status.available == 0 && status.size >= self.config.max_size
+    }
+
+    /// Number of connection failures observed since the last successful operation.
+    pub fn recent_failure_count(&self) -> u64 {
+        self.recent_failures.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl<M: PoolManager> StateStore for PooledStore<M> {
+    async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+        self.with_retry(|conn| {
+            let snapshot = snapshot.clone();
+            async move { conn.upsert_latest(snapshot).await }
+        })
+        .await
+    }
+
+    async fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
+        self.with_retry(|conn| {
+            let user_id = user_id.to_string();
+            async move { conn.get_latest(&user_id).await }
+        })
+        .await
+    }
+
+    async fn delete(&self, user_id: &str) -> Result<(), StoreError> {
+        self.with_retry(|conn| {
+            let user_id = user_id.to_string();
+            async move { conn.delete(&user_id).await }
+        })
+        .await
+    }
+
+    async fn get_history(&self, user_id: &str, limit: usize) -> Result<Vec<StateSnapshot>, StoreError> {
+        self.with_retry(|conn| {
+            let user_id = user_id.to_string();
+            async move { conn.get_history(&user_id, limit).await }
+        })
+        .await
+    }
+
+    async fn health_check(&self) -> Result<bool, StoreError> {
+        Ok(!self.is_saturated())
+    }
+}
+
+#[async_trait]
+impl<M: PoolManager> HealthCheck for PooledStore<M> {
+    async fn check(&self) -> ComponentHealth {
+        if self.is_saturated() {
+            ComponentHealth::degraded("pooled_store", "connection pool exhausted")
+        } else if self.recent_failure_count() > 0 {
+            ComponentHealth::degraded("pooled_store", "recent connection failures")
+        } else {
+            ComponentHealth::healthy("pooled_store")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryStore;
+    use attuned_core::Source;
+    use std::sync::atomic::AtomicUsize;
+
+    /// A connection that fails its first few operations with a transient
+    /// [`StoreError::Connection`], then succeeds, so `with_retry`'s retry
+    /// path (and its effect on `recent_failures`) can be exercised without a
+    /// real remote backend.
+    struct FlakyConnection {
+        inner: MemoryStore,
+        remaining_failures: Arc<AtomicUsize>,
+    }
+
+    impl FlakyConnection {
+        fn maybe_fail(&self) -> Result<(), StoreError> {
+            if self
+                .remaining_failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| Some(n.saturating_sub(1)))
+                .unwrap()
+                > 0
+            {
+                Err(StoreError::connection("simulated transient failure"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl StateStore for FlakyConnection {
+        async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+            self.maybe_fail()?;
+            self.inner.upsert_latest(snapshot).await
+        }
+
+        async fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
+            self.maybe_fail()?;
+            self.inner.get_latest(user_id).await
+        }
+
+        async fn delete(&self, user_id: &str) -> Result<(), StoreError> {
+            self.maybe_fail()?;
+            self.inner.delete(user_id).await
+        }
+
+        async fn get_history(&self, user_id: &str, limit: usize) -> Result<Vec<StateSnapshot>, StoreError> {
+            self.maybe_fail()?;
+            self.inner.get_history(user_id, limit).await
+        }
+
+        async fn health_check(&self) -> Result<bool, StoreError> {
+            Ok(true)
+        }
+    }
+
+    #[async_trait]
+    impl HealthCheck for FlakyConnection {
+        async fn check(&self) -> ComponentHealth {
+            ComponentHealth::healthy("flaky")
+        }
+    }
+
+    struct FlakyManager {
+        remaining_failures: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl PoolManager for FlakyManager {
+        type Connection = FlakyConnection;
+
+        async fn create(&self) -> Result<Self::Connection, StoreError> {
+            Ok(FlakyConnection {
+                inner: MemoryStore::default(),
+                remaining_failures: self.remaining_failures.clone(),
+            })
+        }
+    }
+
+    fn snapshot(user_id: &str) -> StateSnapshot {
+        StateSnapshot::builder()
+            .user_id(user_id)
+            .source(Source::SelfReport)
+            .axis("warmth", 0.5)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_recent_failure_count_resets_after_success() {
+        let manager = FlakyManager {
+            remaining_failures: Arc::new(AtomicUsize::new(2)),
+        };
+        let store = PooledStore::new(manager, PooledStoreConfig::default()).unwrap();
+
+        // Two transient failures, absorbed by the default retry policy
+        // (3 retries), must not leave `recent_failure_count` permanently
+        // nonzero once the operation ultimately succeeds.
+        store.upsert_latest(snapshot("user_1")).await.unwrap();
+        assert_eq!(store.recent_failure_count(), 0);
+    }
+}
@@ -0,0 +1,543 @@
+//! Leaderless replication for [`StateStore`] across processes without a
+//! shared database.
+//!
+//! Each writer (a "host") keeps its own append-only log of immutable
+//! [`Record`]s, ordered purely by `(host_id, idx)` — a flat monotonic
+//! counter per host, not a linked-list of parent pointers, so a peer can
+//! always ask "everything after idx N from host H" as a plain range query.
+//! [`ReplicatedStore::sync`] pulls the missing tail from a remote
+//! [`RecordSink`] and resolves conflicts by keeping whichever snapshot was
+//! observed most recently.
+
+use crate::error::StoreError;
+use crate::traits::StateStore;
+use async_trait::async_trait;
+use attuned_core::StateSnapshot;
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A single immutable replication record.
+///
+/// `idx` is 1-based and dense within `host_id`'s log: the first record a
+/// host appends has `idx == 1`, the second `idx == 2`, and so on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    /// The host that authored this record.
+    pub host_id: String,
+    /// Position of this record within `host_id`'s log.
+    pub idx: u64,
+    /// The write this record replicates: a state upsert or a tombstone.
+    pub payload: RecordPayload,
+    /// When the authoring host observed this value (Unix ms), used to
+    /// resolve conflicts between records from different hosts for the same
+    /// user: the newest `observed_at_unix_ms` wins.
+    pub observed_at_unix_ms: i64,
+}
+
+/// The write a [`Record`] replicates.
+///
+/// A tombstone lets a deletion propagate through [`ReplicatedStore::sync`]
+/// the same way an upsert does — without it, a peer that still holds the
+/// pre-deletion snapshot would resurrect it on the next sync.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordPayload {
+    /// A state upsert, applied to the inner store as-is.
+    Upsert(StateSnapshot),
+    /// A deletion of `user_id`'s state, applied as a delete on the inner
+    /// store.
+    Delete {
+        /// The user whose state was deleted.
+        user_id: String,
+    },
+}
+
+impl RecordPayload {
+    /// The user this payload pertains to, regardless of variant.
+    fn user_id(&self) -> &str {
+        match self {
+            RecordPayload::Upsert(snapshot) => &snapshot.user_id,
+            RecordPayload::Delete { user_id } => user_id,
+        }
+    }
+}
+
+/// Local storage for one or more hosts' replication logs.
+#[async_trait]
+pub trait RecordStore: Send + Sync {
+    /// The idx that would be assigned to the next record appended for
+    /// `host_id` (1 if the host has no records yet).
+    ///
+    /// This is a point-in-time read, not a reservation — don't use it to
+    /// compute an idx for a record you're about to append yourself; use
+    /// [`append_next`](Self::append_next) instead, which assigns the idx
+    /// and appends atomically. `next_idx` remains useful for read-only
+    /// purposes such as reporting the highest idx a peer has.
+    async fn next_idx(&self, host_id: &str) -> u64;
+
+    /// Append a record to `record.host_id`'s log.
+    ///
+    /// Implementations must reject records whose `idx` isn't exactly
+    /// `next_idx(&record.host_id)` — the log is dense and append-only. Only
+    /// use this for records whose idx was already assigned by a remote peer
+    /// (e.g. when applying synced records); for locally originated writes,
+    /// use [`append_next`](Self::append_next) so idx assignment and the
+    /// append happen under one lock, with no gap a concurrent writer for
+    /// the same host could race into.
+    async fn append(&self, record: Record) -> Result<(), StoreError>;
+
+    /// Atomically assign the next idx for `host_id` and append the record
+    /// `build_record` constructs from it, under a single critical section.
+    ///
+    /// This is the host-local counterpart to `append`: calling `next_idx`
+    /// and `append` as two separate steps leaves a window where a
+    /// concurrent local write for the same host can observe the same "next"
+    /// idx and lose the append race with a spurious out-of-order error,
+    /// even though neither caller did anything wrong. Returns the appended
+    /// record (with its assigned idx filled in).
+    async fn append_next(
+        &self,
+        host_id: &str,
+        build_record: Box<dyn FnOnce(u64) -> Record + Send>,
+    ) -> Result<Record, StoreError>;
+
+    /// Records for `host_id` with `idx` in the exclusive-start range
+    /// `(from, to]`. `from` is typically the highest idx the caller already
+    /// has, so the result is exactly the missing tail.
+    async fn range(&self, host_id: &str, from: u64, to: u64) -> Result<Vec<Record>, StoreError>;
+
+    /// All host ids this store currently holds any records for.
+    async fn known_hosts(&self) -> Vec<String>;
+}
+
+/// What a sync peer exposes about its replication logs.
+///
+/// Blanket-implemented for every [`RecordStore`], so the simplest peer is
+/// just another in-process (or, eventually, RPC-wrapped) `RecordStore`.
+#[async_trait]
+pub trait RecordSink: Send + Sync {
+    /// All host ids the peer currently holds any records for.
+    async fn known_hosts(&self) -> Vec<String>;
+
+    /// The highest idx the peer has observed for `host_id` (0 if none).
+    async fn highest_idx(&self, host_id: &str) -> u64;
+
+    /// Records for `host_id` with `idx` in `(from, to]`.
+    async fn range(&self, host_id: &str, from: u64, to: u64) -> Result<Vec<Record>, StoreError>;
+}
+
+#[async_trait]
+impl<T: RecordStore> RecordSink for T {
+    async fn known_hosts(&self) -> Vec<String> {
+        RecordStore::known_hosts(self).await
+    }
+
+    async fn highest_idx(&self, host_id: &str) -> u64 {
+        RecordStore::next_idx(self, host_id).await.saturating_sub(1)
+    }
+
+    async fn range(&self, host_id: &str, from: u64, to: u64) -> Result<Vec<Record>, StoreError> {
+        RecordStore::range(self, host_id, from, to).await
+    }
+}
+
+/// In-memory [`RecordStore`], suitable for a single process and as the
+/// reference implementation a Qdrant-backed one can be checked against.
+#[derive(Default)]
+pub struct MemoryRecordStore {
+    logs: DashMap<String, Vec<Record>>,
+}
+
+impl MemoryRecordStore {
+    /// Create an empty record store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RecordStore for MemoryRecordStore {
+    async fn next_idx(&self, host_id: &str) -> u64 {
+        self.logs.get(host_id).map(|log| log.len() as u64 + 1).unwrap_or(1)
+    }
+
+    async fn append(&self, record: Record) -> Result<(), StoreError> {
+        let mut log = self.logs.entry(record.host_id.clone()).or_default();
+        let expected = log.len() as u64 + 1;
+        if record.idx != expected {
+            return Err(StoreError::internal(format!(
+                "out-of-order append for host '{}': expected idx {expected}, got {}",
+                record.host_id, record.idx
+            )));
+        }
+        log.push(record);
+        Ok(())
+    }
+
+    async fn append_next(
+        &self,
+        host_id: &str,
+        build_record: Box<dyn FnOnce(u64) -> Record + Send>,
+    ) -> Result<Record, StoreError> {
+        // Holding the `DashMap` entry guard across idx assignment and the
+        // push is what makes this atomic: no other caller can observe the
+        // same "next" idx until this one has either pushed or dropped it.
+        let mut log = self.logs.entry(host_id.to_string()).or_default();
+        let idx = log.len() as u64 + 1;
+        let record = build_record(idx);
+        log.push(record.clone());
+        Ok(record)
+    }
+
+    async fn range(&self, host_id: &str, from: u64, to: u64) -> Result<Vec<Record>, StoreError> {
+        let Some(log) = self.logs.get(host_id) else {
+            return Ok(vec![]);
+        };
+        // idx is 1-based and dense, so record idx N lives at log[N - 1].
+        let start = from as usize;
+        let end = (to as usize).min(log.len());
+        if start >= end {
+            return Ok(vec![]);
+        }
+        Ok(log[start..end].to_vec())
+    }
+
+    async fn known_hosts(&self) -> Vec<String> {
+        self.logs.iter().map(|entry| entry.key().clone()).collect()
+    }
+}
+
+fn now_unix_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Wraps a [`StateStore`] with replication: every local write is appended to
+/// this host's log before being applied, and [`sync`](Self::sync) pulls in
+/// writes from other hosts.
+pub struct ReplicatedStore<S: StateStore> {
+    inner: S,
+    host_id: String,
+    log: Arc<dyn RecordStore>,
+    /// Newest `observed_at_unix_ms` applied per user, across both local
+    /// writes and synced records, used to resolve conflicts on `sync`.
+    latest_observed: DashMap<String, i64>,
+}
+
+impl<S: StateStore> ReplicatedStore<S> {
+    /// Wrap `inner`, identifying this process's writes as `host_id`, with a
+    /// fresh in-memory replication log.
+    pub fn new(inner: S, host_id: impl Into<String>) -> Self {
+        Self::with_log(inner, host_id, Arc::new(MemoryRecordStore::new()))
+    }
+
+    /// Wrap `inner` with a custom [`RecordStore`] backing the log (e.g. a
+    /// Qdrant-backed one, for a log that survives process restarts).
+    pub fn with_log(inner: S, host_id: impl Into<String>, log: Arc<dyn RecordStore>) -> Self {
+        Self {
+            inner,
+            host_id: host_id.into(),
+            log,
+            latest_observed: DashMap::new(),
+        }
+    }
+
+    /// This store's host id, used as the key for its own replication log.
+    pub fn host_id(&self) -> &str {
+        &self.host_id
+    }
+
+    /// Pull any records this store is missing from `remote` and apply them
+    /// in `(host_id, idx)` order, keeping whichever snapshot was observed
+    /// most recently on a per-user conflict. Returns the number of records
+    /// applied.
+    #[tracing::instrument(skip(self, remote))]
+    pub async fn sync(&self, remote: &dyn RecordSink) -> Result<usize, StoreError> {
+        let mut applied = 0;
+
+        for host in remote.known_hosts().await {
+            let local_highest = self.log.next_idx(&host).await.saturating_sub(1);
+            let remote_highest = remote.highest_idx(&host).await;
+
+            if remote_highest <= local_highest {
+                continue;
+            }
+
+            let missing = remote.range(&host, local_highest, remote_highest).await?;
+            for record in missing {
+                if self.apply(record).await? {
+                    applied += 1;
+                }
+            }
+        }
+
+        tracing::debug!(applied, "replication sync complete");
+        Ok(applied)
+    }
+
+    /// Apply a replicated record, appending it to its host's log and
+    /// updating the inner store only if it isn't older than what we've
+    /// already applied for that user (on an equal timestamp the record
+    /// applied later wins). Returns whether it was applied.
+    async fn apply(&self, record: Record) -> Result<bool, StoreError> {
+        let user_id = record.payload.user_id().to_string();
+        let observed_at = record.observed_at_unix_ms;
+
+        self.log.append(record.clone()).await?;
+
+        let is_newer = match self.latest_observed.get(&user_id) {
+            Some(current) => observed_at >= *current,
+            None => true,
+        };
+        if !is_newer {
+            return Ok(false);
+        }
+
+        match record.payload {
+            RecordPayload::Upsert(snapshot) => self.inner.upsert_latest(snapshot).await?,
+            RecordPayload::Delete { user_id: deleted } => self.inner.delete(&deleted).await?,
+        }
+        self.latest_observed.insert(user_id, observed_at);
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> RecordSink for ReplicatedStore<S> {
+    async fn known_hosts(&self) -> Vec<String> {
+        self.log.known_hosts().await
+    }
+
+    async fn highest_idx(&self, host_id: &str) -> u64 {
+        self.log.next_idx(host_id).await.saturating_sub(1)
+    }
+
+    async fn range(&self, host_id: &str, from: u64, to: u64) -> Result<Vec<Record>, StoreError> {
+        self.log.range(host_id, from, to).await
+    }
+}
+
+#[async_trait]
+impl<S: StateStore> StateStore for ReplicatedStore<S> {
+    #[tracing::instrument(skip(self, snapshot), fields(user_id = %snapshot.user_id, host_id = %self.host_id))]
+    async fn upsert_latest(&self, snapshot: StateSnapshot) -> Result<(), StoreError> {
+        snapshot.validate()?;
+
+        let observed_at = now_unix_ms();
+        let user_id = snapshot.user_id.clone();
+        let host_id = self.host_id.clone();
+        let record_snapshot = snapshot.clone();
+        self.log
+            .append_next(
+                &self.host_id,
+                Box::new(move |idx| Record {
+                    host_id,
+                    idx,
+                    payload: RecordPayload::Upsert(record_snapshot),
+                    observed_at_unix_ms: observed_at,
+                }),
+            )
+            .await?;
+        self.inner.upsert_latest(snapshot).await?;
+        self.latest_observed.insert(user_id, observed_at);
+
+        Ok(())
+    }
+
+    async fn get_latest(&self, user_id: &str) -> Result<Option<StateSnapshot>, StoreError> {
+        self.inner.get_latest(user_id).await
+    }
+
+    /// Delete `user_id`'s state locally and append a tombstone to this
+    /// host's replication log, so the deletion propagates on the next
+    /// [`sync`](Self::sync) instead of a peer resurrecting the old snapshot.
+    async fn delete(&self, user_id: &str) -> Result<(), StoreError> {
+        let observed_at = now_unix_ms();
+        let host_id = self.host_id.clone();
+        let deleted_user_id = user_id.to_string();
+        self.log
+            .append_next(
+                &self.host_id,
+                Box::new(move |idx| Record {
+                    host_id,
+                    idx,
+                    payload: RecordPayload::Delete { user_id: deleted_user_id },
+                    observed_at_unix_ms: observed_at,
+                }),
+            )
+            .await?;
+        self.inner.delete(user_id).await?;
+        self.latest_observed.insert(user_id.to_string(), observed_at);
+
+        Ok(())
+    }
+
+    async fn get_history(&self, user_id: &str, limit: usize) -> Result<Vec<StateSnapshot>, StoreError> {
+        self.inner.get_history(user_id, limit).await
+    }
+
+    async fn health_check(&self) -> Result<bool, StoreError> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryStore;
+    use attuned_core::Source;
+
+    fn snapshot(user_id: &str, warmth: f32) -> StateSnapshot {
+        StateSnapshot::builder()
+            .user_id(user_id)
+            .source(Source::SelfReport)
+            .axis("warmth", warmth)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_record_store_next_idx_and_append() {
+        let log = MemoryRecordStore::new();
+        assert_eq!(log.next_idx("host-a").await, 1);
+
+        log.append(Record {
+            host_id: "host-a".to_string(),
+            idx: 1,
+            payload: RecordPayload::Upsert(snapshot("user_1", 0.5)),
+            observed_at_unix_ms: 100,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(log.next_idx("host-a").await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_record_store_rejects_out_of_order_append() {
+        let log = MemoryRecordStore::new();
+        let result = log
+            .append(Record {
+                host_id: "host-a".to_string(),
+                idx: 2,
+                payload: RecordPayload::Upsert(snapshot("user_1", 0.5)),
+                observed_at_unix_ms: 100,
+            })
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_record_store_range() {
+        let log = MemoryRecordStore::new();
+        for i in 1..=5u64 {
+            log.append(Record {
+                host_id: "host-a".to_string(),
+                idx: i,
+                payload: RecordPayload::Upsert(snapshot("user_1", i as f32 / 10.0)),
+                observed_at_unix_ms: i as i64,
+            })
+            .await
+            .unwrap();
+        }
+
+        let tail = log.range("host-a", 2, 5).await.unwrap();
+        assert_eq!(tail.len(), 3);
+        assert_eq!(tail[0].idx, 3);
+        assert_eq!(tail.last().unwrap().idx, 5);
+    }
+
+    #[tokio::test]
+    async fn test_sync_pulls_remote_writes() {
+        let local = ReplicatedStore::new(MemoryStore::default(), "host-a");
+        let remote = ReplicatedStore::new(MemoryStore::default(), "host-b");
+
+        remote.upsert_latest(snapshot("user_1", 0.9)).await.unwrap();
+
+        let applied = local.sync(&remote).await.unwrap();
+        assert_eq!(applied, 1);
+
+        let synced = local.get_latest("user_1").await.unwrap().unwrap();
+        assert_eq!(synced.axes["warmth"], 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_sync_is_idempotent() {
+        let local = ReplicatedStore::new(MemoryStore::default(), "host-a");
+        let remote = ReplicatedStore::new(MemoryStore::default(), "host-b");
+
+        remote.upsert_latest(snapshot("user_1", 0.9)).await.unwrap();
+
+        local.sync(&remote).await.unwrap();
+        let applied_again = local.sync(&remote).await.unwrap();
+        assert_eq!(applied_again, 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_appends_tombstone_and_propagates_on_sync() {
+        let local = ReplicatedStore::new(MemoryStore::default(), "host-a");
+        let remote = ReplicatedStore::new(MemoryStore::default(), "host-b");
+
+        remote.upsert_latest(snapshot("user_1", 0.9)).await.unwrap();
+        local.sync(&remote).await.unwrap();
+        assert!(local.get_latest("user_1").await.unwrap().is_some());
+
+        remote.delete("user_1").await.unwrap();
+        let applied = local.sync(&remote).await.unwrap();
+        assert_eq!(applied, 1);
+        assert!(local.get_latest("user_1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sync_does_not_resurrect_after_tombstone() {
+        let local = ReplicatedStore::new(MemoryStore::default(), "host-a");
+        let remote = ReplicatedStore::new(MemoryStore::default(), "host-b");
+
+        remote.upsert_latest(snapshot("user_1", 0.9)).await.unwrap();
+        remote.delete("user_1").await.unwrap();
+        local.sync(&remote).await.unwrap();
+        assert!(local.get_latest("user_1").await.unwrap().is_none());
+
+        // A peer whose only record predates the deletion must not resurrect
+        // the user when it's synced afterwards.
+        let stale_log = MemoryRecordStore::new();
+        stale_log
+            .append(Record {
+                host_id: "host-c".to_string(),
+                idx: 1,
+                payload: RecordPayload::Upsert(snapshot("user_1", 0.9)),
+                observed_at_unix_ms: 1,
+            })
+            .await
+            .unwrap();
+
+        local.sync(&stale_log).await.unwrap();
+        assert!(local.get_latest("user_1").await.unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_upserts_all_get_distinct_log_indices() {
+        let store = Arc::new(ReplicatedStore::new(MemoryStore::default(), "host-a"));
+
+        let handles: Vec<_> = (0..10)
+            .map(|i| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    store.upsert_latest(snapshot(&format!("user_{i}"), i as f32 / 10.0)).await
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        let records = store.range(store.host_id(), 0, 10).await.unwrap();
+        assert_eq!(records.len(), 10);
+        let mut indices: Vec<u64> = records.iter().map(|r| r.idx).collect();
+        indices.sort_unstable();
+        assert_eq!(indices, (1..=10).collect::<Vec<_>>());
+    }
+}
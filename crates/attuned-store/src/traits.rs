@@ -50,4 +50,37 @@ pub trait StateStore: Send + Sync {
     async fn health_check(&self) -> Result<bool, StoreError> {
         Ok(true)
     }
+
+    /// Insert or update multiple snapshots in one call.
+    ///
+    /// Returns one result per input snapshot, in the same order, so a
+    /// failure on one item (e.g. a validation error) doesn't prevent the
+    /// others from being stored. Default implementation loops over
+    /// [`StateStore::upsert_latest`]; stores with native bulk-write support
+    /// should override this.
+    async fn upsert_many(&self, snapshots: Vec<StateSnapshot>) -> Vec<Result<(), StoreError>> {
+        let mut results = Vec::with_capacity(snapshots.len());
+        for snapshot in snapshots {
+            results.push(self.upsert_latest(snapshot).await);
+        }
+        results
+    }
+
+    /// Get the latest snapshot for each of `user_ids`, in the same order.
+    ///
+    /// Returns one result per requested ID: `Ok(None)` when no state exists
+    /// for that user, `Err` only for a genuine store failure on that
+    /// lookup. Default implementation loops over
+    /// [`StateStore::get_latest`]; stores with native bulk-read support
+    /// should override this.
+    async fn get_many(
+        &self,
+        user_ids: &[String],
+    ) -> Vec<Result<Option<StateSnapshot>, StoreError>> {
+        let mut results = Vec::with_capacity(user_ids.len());
+        for user_id in user_ids {
+            results.push(self.get_latest(user_id).await);
+        }
+        results
+    }
 }